@@ -0,0 +1,356 @@
+use crate::price::PriceList;
+use crate::{
+    Book, Contributor, ExtraFields, Format, Grammar, Isbn, LanguageTag, Price, PriceTag,
+    PublicationDate, Rating, RatingScale, Rule, Series, SourceRating,
+};
+use pest::Parser;
+use thiserror::Error;
+
+/// The earliest publication year, 9999 BCE.
+pub const MIN_YEAR: i32 = -9999;
+pub const MAX_YEAR: i32 = 9999;
+
+/// A field value that is well-formed but outside what a `Book` may hold.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid `{field}`: {message}")]
+pub struct ValidationError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(field: &'static str, message: impl Into<String>) -> Self {
+        ValidationError {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+pub(crate) fn validate_title(title: &str) -> Result<(), ValidationError> {
+    if title.trim().is_empty() {
+        return Err(ValidationError::new(
+            "book_title",
+            "title must not be empty",
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_year(year: i32) -> Result<(), ValidationError> {
+    if year == 0 || !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+        return Err(ValidationError::new(
+            "publication_year",
+            format!(
+                "year must be between {} BCE and {} and cannot be 0",
+                -MIN_YEAR, MAX_YEAR
+            ),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_rating(rating: &Rating) -> Result<(), ValidationError> {
+    rating
+        .check()
+        .map_err(|e| ValidationError::new("rating", e.to_string()))
+}
+
+/// Checks each price and that no two of them price the same offer.
+pub(crate) fn validate_prices(prices: &[PriceTag]) -> Result<(), ValidationError> {
+    for (index, tag) in prices.iter().enumerate() {
+        tag.check()
+            .map_err(|e| ValidationError::new("price", e.to_string()))?;
+        if prices[..index].iter().any(|seen| seen.same_offer(tag)) {
+            return Err(ValidationError::new(
+                "price",
+                format!(
+                    "`{}` repeats the currency, format and dates of another price",
+                    tag
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_text(field: &'static str, text: &str) -> Result<(), ValidationError> {
+    if text.trim().is_empty() {
+        return Err(ValidationError::new(
+            field,
+            format!("{} must not be empty", field),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_pages(pages: u32) -> Result<(), ValidationError> {
+    if pages == 0 {
+        return Err(ValidationError::new(
+            "pages",
+            format!("pages must be between 1 and {}", u32::MAX),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that `key` would be read back as the key of an extension field rather than as a known
+/// field, a book header or not at all.
+pub(crate) fn validate_extra_key(key: &str) -> Result<(), ValidationError> {
+    let line = format!("{}: x\n", key);
+    let parsed_key = Grammar::parse(Rule::extra_field, &line)
+        .ok()
+        .and_then(|mut pairs| pairs.next())
+        .and_then(|field| {
+            field
+                .into_inner()
+                .find(|inner| inner.as_rule() == Rule::field_key)
+        });
+    // The parser trims the key it reads, so surrounding spaces would not survive a round trip.
+    if key != key.trim() || parsed_key.is_none_or(|parsed| parsed.as_str() != key) {
+        return Err(ValidationError::new(
+            "extra",
+            format!("`{}` cannot be used as an extension field name", key),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_note(note: &str) -> Result<(), ValidationError> {
+    if !(note.starts_with('#') || note.starts_with("//")) || note.contains('\n') {
+        return Err(ValidationError::new(
+            "notes",
+            "a note must be a single line starting with `#` or `//`",
+        ));
+    }
+    Ok(())
+}
+
+/// Builds a [`Book`] in code, applying the same checks as the parser.
+#[derive(Debug, Clone, Default)]
+pub struct BookBuilder {
+    id: u64,
+    book_title: Option<String>,
+    authors: Vec<Contributor>,
+    genres: Vec<String>,
+    publication_year: Option<PublicationDate>,
+    rating: Option<Rating>,
+    ratings: Vec<SourceRating>,
+    prices: Vec<PriceTag>,
+    isbn: Option<Isbn>,
+    publisher: Option<String>,
+    pages: Option<u32>,
+    language: Option<LanguageTag>,
+    format: Option<Format>,
+    edition: Option<String>,
+    series: Option<Series>,
+    description: Option<String>,
+    extra: ExtraFields,
+    notes: Vec<String>,
+}
+
+impl BookBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.book_title = Some(title.into());
+        self
+    }
+
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.authors.push(Contributor::author(author));
+        self
+    }
+
+    pub fn authors<I, S>(mut self, authors: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.authors
+            .extend(authors.into_iter().map(Contributor::author));
+        self
+    }
+
+    /// Adds a contributor with a role other than author, e.g. a translator.
+    pub fn contributor(mut self, contributor: Contributor) -> Self {
+        self.authors.push(contributor);
+        self
+    }
+
+    pub fn genre(mut self, genre: impl Into<String>) -> Self {
+        self.genres.push(genre.into());
+        self
+    }
+
+    pub fn genres<I, S>(mut self, genres: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.genres.extend(genres.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the publication year; negative years are before the common era.
+    pub fn publication_year(mut self, year: i32) -> Self {
+        self.publication_year = Some(PublicationDate::year_unchecked(year));
+        self
+    }
+
+    pub fn publication_date(mut self, date: PublicationDate) -> Self {
+        self.publication_year = Some(date);
+        self
+    }
+
+    /// Sets a rating on the 0–10 scale.
+    pub fn rating(mut self, rating: f32) -> Self {
+        self.rating = Some(Rating::unchecked(rating, RatingScale::Ten));
+        self
+    }
+
+    pub fn scaled_rating(mut self, rating: Rating) -> Self {
+        self.rating = Some(rating);
+        self
+    }
+
+    /// Adds a rating from another source to the `Ratings:` list.
+    pub fn source_rating(mut self, rating: SourceRating) -> Self {
+        self.ratings.push(rating);
+        self
+    }
+
+    /// Adds a plain `Price:` line.
+    pub fn price(mut self, price: Price) -> Self {
+        self.prices.push(PriceTag::new(price));
+        self
+    }
+
+    /// Adds a `Price:` line with a discount, format or validity dates.
+    pub fn price_tag(mut self, tag: PriceTag) -> Self {
+        self.prices.push(tag);
+        self
+    }
+
+    pub fn isbn(mut self, isbn: Isbn) -> Self {
+        self.isbn = Some(isbn);
+        self
+    }
+
+    pub fn publisher(mut self, publisher: impl Into<String>) -> Self {
+        self.publisher = Some(publisher.into());
+        self
+    }
+
+    pub fn pages(mut self, pages: u32) -> Self {
+        self.pages = Some(pages);
+        self
+    }
+
+    pub fn language(mut self, language: LanguageTag) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn edition(mut self, edition: impl Into<String>) -> Self {
+        self.edition = Some(edition.into());
+        self
+    }
+
+    pub fn series(mut self, name: impl Into<String>, index: u32) -> Self {
+        self.series = Some(Series::new(name, index));
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Adds an extension field, written as a `Key: value` line.
+    pub fn extra(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra.insert(key, value);
+        self
+    }
+
+    /// Adds a comment, written with its `#` or `//` marker.
+    pub fn note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Book, ValidationError> {
+        let book_title = self
+            .book_title
+            .ok_or_else(|| ValidationError::new("book_title", "title is required"))?;
+        validate_title(&book_title)?;
+
+        let publication_year = self.publication_year.ok_or_else(|| {
+            ValidationError::new("publication_year", "publication year is required")
+        })?;
+        validate_year(publication_year.year())?;
+
+        if let Some(rating) = &self.rating {
+            validate_rating(rating)?;
+        }
+        for rating in &self.ratings {
+            validate_text("ratings", rating.source())?;
+            validate_rating(&rating.rating())?;
+        }
+        validate_prices(&self.prices)?;
+        if let Some(publisher) = &self.publisher {
+            validate_text("publisher", publisher)?;
+        }
+        if let Some(pages) = self.pages {
+            validate_pages(pages)?;
+        }
+        if let Some(edition) = &self.edition {
+            validate_text("edition", edition)?;
+        }
+        if let Some(series) = &self.series {
+            validate_text("series", series.name())?;
+        }
+        if let Some(description) = &self.description {
+            validate_text("description", description)?;
+        }
+        for (key, _) in self.extra.iter() {
+            validate_extra_key(key)?;
+        }
+        for note in &self.notes {
+            validate_note(note)?;
+        }
+
+        Ok(Book {
+            id: self.id,
+            book_title,
+            authors: self.authors,
+            genres: self.genres,
+            publication_year,
+            rating: self.rating,
+            ratings: self.ratings,
+            prices: PriceList(self.prices),
+            isbn: self.isbn,
+            publisher: self.publisher,
+            pages: self.pages,
+            language: self.language,
+            format: self.format,
+            edition: self.edition,
+            series: self.series,
+            description: self.description,
+            extra: self.extra,
+            notes: self.notes,
+        })
+    }
+}
@@ -0,0 +1,276 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Lowercase words that belong to the surname when they come right before it, as in
+/// "Ludwig van Beethoven".
+const PARTICLES: &[&str] = &[
+    "al", "bin", "da", "de", "del", "della", "den", "der", "des", "di", "du", "ibn", "la", "le",
+    "ten", "ter", "van", "von",
+];
+
+/// Generational suffixes that follow the surname, as in "Martin Luther King Jr.".
+const SUFFIXES: &[&str] = &["Jr.", "Jr", "Sr.", "Sr", "II", "III", "IV"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unknown contributor role `{0}`, expected author, translator, editor or illustrator")]
+pub struct UnknownRole(pub String);
+
+/// What a contributor did for a book, written in parentheses after the name in `Authors:`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    Author,
+    Translator,
+    Editor,
+    Illustrator,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Author => "author",
+            Role::Translator => "translator",
+            Role::Editor => "editor",
+            Role::Illustrator => "illustrator",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = UnknownRole;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "author" => Ok(Role::Author),
+            "translator" => Ok(Role::Translator),
+            "editor" => Ok(Role::Editor),
+            "illustrator" => Ok(Role::Illustrator),
+            _ => Err(UnknownRole(text.to_string())),
+        }
+    }
+}
+
+/// A person named in the `Authors:` list together with their role.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Contributor {
+    name: String,
+    role: Role,
+}
+
+impl Contributor {
+    pub fn new(name: impl Into<String>, role: Role) -> Self {
+        Contributor {
+            name: name.into(),
+            role,
+        }
+    }
+
+    pub fn author(name: impl Into<String>) -> Self {
+        Self::new(name, Role::Author)
+    }
+
+    /// Reads the catalog form, e.g. `Jane Doe (translator)`. A parenthesised suffix that is not a
+    /// known role is kept as part of the name.
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        let role = text
+            .strip_suffix(')')
+            .and_then(|rest| rest.rsplit_once('('))
+            .and_then(|(name, role)| Some((name.trim_end(), role.parse::<Role>().ok()?)));
+        match role {
+            Some((name, role)) if !name.is_empty() => Self::new(name, role),
+            _ => Self::author(text),
+        }
+    }
+
+    /// The name as written in the catalog.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Everything before the surname, e.g. "Travis" for both "Travis Casey" and "Casey, Travis".
+    pub fn given_name(&self) -> Option<&str> {
+        self.parts().given
+    }
+
+    /// The family name including any particles, e.g. "van Beethoven".
+    pub fn surname(&self) -> &str {
+        self.parts().surname
+    }
+
+    /// The name in "Surname, Given" order with particles moved to the end, e.g.
+    /// "Beethoven, Ludwig van".
+    pub fn inverted_name(&self) -> String {
+        let parts = self.parts();
+        let (particles, surname) = split_particles(parts.surname);
+        let mut inverted = surname.to_string();
+        let rest: Vec<&str> = parts.given.into_iter().chain(particles).collect();
+        if !rest.is_empty() {
+            inverted.push_str(", ");
+            inverted.push_str(&rest.join(" "));
+        }
+        if let Some(suffix) = parts.suffix {
+            inverted.push_str(", ");
+            inverted.push_str(suffix);
+        }
+        inverted
+    }
+
+    /// A case-insensitive key for sorting contributors by surname.
+    pub fn sort_key(&self) -> String {
+        self.inverted_name().to_lowercase()
+    }
+
+    fn parts(&self) -> NameParts<'_> {
+        NameParts::split(&self.name)
+    }
+}
+
+impl fmt::Display for Contributor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.role {
+            Role::Author => f.write_str(&self.name),
+            role => write!(f, "{} ({})", self.name, role),
+        }
+    }
+}
+
+struct NameParts<'a> {
+    given: Option<&'a str>,
+    surname: &'a str,
+    suffix: Option<&'a str>,
+}
+
+impl<'a> NameParts<'a> {
+    fn split(name: &'a str) -> Self {
+        let name = name.trim();
+        let non_empty = |part: &'a str| Some(part.trim()).filter(|part| !part.is_empty());
+
+        // "Casey, Travis" or "King, Martin Luther, Jr."
+        if let Some((surname, rest)) = name.split_once(',') {
+            let (given, suffix) = match rest.split_once(',') {
+                Some((given, suffix)) => (given, non_empty(suffix)),
+                None => (rest, None),
+            };
+            return NameParts {
+                given: non_empty(given),
+                surname: surname.trim(),
+                suffix,
+            };
+        }
+
+        let words = word_ranges(name);
+        let mut last = words.len();
+        let mut suffix = None;
+        if words.len() > 2 && SUFFIXES.contains(&&name[words[last - 1].0..words[last - 1].1]) {
+            suffix = Some(&name[words[last - 1].0..]);
+            last -= 1;
+        }
+        if last == 0 {
+            return NameParts {
+                given: None,
+                surname: name,
+                suffix,
+            };
+        }
+
+        let mut first = last - 1;
+        while first > 1 && PARTICLES.contains(&&name[words[first - 1].0..words[first - 1].1]) {
+            first -= 1;
+        }
+        NameParts {
+            given: (first > 0).then(|| &name[..words[first - 1].1]),
+            surname: &name[words[first].0..words[last - 1].1],
+            suffix,
+        }
+    }
+}
+
+/// Byte ranges of the whitespace-separated words in `text`.
+fn word_ranges(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(begin)) => {
+                ranges.push((begin, index));
+                start = None;
+            }
+            (false, None) => start = Some(index),
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        ranges.push((begin, text.len()));
+    }
+    ranges
+}
+
+/// Splits leading particles off a surname: "van der Berg" gives (["van", "der"], "Berg").
+fn split_particles(surname: &str) -> (Vec<&str>, &str) {
+    let mut particles = Vec::new();
+    let mut rest = surname;
+    while let Some((word, tail)) = rest.split_once(char::is_whitespace) {
+        if !PARTICLES.contains(&word) {
+            break;
+        }
+        particles.push(word);
+        rest = tail.trim_start();
+    }
+    (particles, rest)
+}
+
+#[derive(Serialize)]
+struct ContributorFields<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    given_name: Option<&'a str>,
+    surname: &'a str,
+    role: Role,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ContributorRepr {
+    Fields {
+        name: String,
+        #[serde(default)]
+        role: Role,
+    },
+    Plain(String),
+}
+
+impl Serialize for Contributor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ContributorFields {
+            name: &self.name,
+            given_name: self.given_name(),
+            surname: self.surname(),
+            role: self.role,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Contributor {
+    /// Accepts both the object form and a plain `"Jane Doe (translator)"` string.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match ContributorRepr::deserialize(deserializer)? {
+            ContributorRepr::Fields { name, role } => Contributor::new(name, role),
+            ContributorRepr::Plain(text) => Contributor::parse(&text),
+        })
+    }
+}
@@ -0,0 +1,281 @@
+use crate::date::{Date, DateError};
+use crate::price::{Currency, Price, PriceError};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    #[error("failed to read rates: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to read JSON rates: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("line {line}: {message}")]
+    InvalidRate { line: usize, message: String },
+    #[error("no exchange rate from {from} to {to}")]
+    MissingRate { from: Currency, to: Currency },
+    #[error("unknown rounding mode `{0}`, expected half-up, half-even, down or up")]
+    UnknownRounding(String),
+    #[error("converted amount is too large")]
+    Overflow,
+}
+
+/// How a converted amount that falls between two minor units is rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Ties go away from zero, as on most price tags.
+    #[default]
+    HalfUp,
+    /// Ties go to the even neighbour (banker's rounding).
+    HalfEven,
+    /// Always towards zero.
+    Down,
+    /// Always away from zero.
+    Up,
+}
+
+impl FromStr for Rounding {
+    type Err = ConvertError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "half-up" => Ok(Rounding::HalfUp),
+            "half-even" => Ok(Rounding::HalfEven),
+            "down" => Ok(Rounding::Down),
+            "up" => Ok(Rounding::Up),
+            _ => Err(ConvertError::UnknownRounding(text.to_string())),
+        }
+    }
+}
+
+impl Rounding {
+    fn divide(self, numerator: u128, denominator: u128) -> u128 {
+        let quotient = numerator / denominator;
+        let remainder = numerator % denominator;
+        if remainder == 0 {
+            return quotient;
+        }
+        let round_up = match self {
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::HalfUp => remainder * 2 >= denominator,
+            Rounding::HalfEven => {
+                remainder * 2 > denominator || (remainder * 2 == denominator && quotient % 2 == 1)
+            }
+        };
+        quotient + u128::from(round_up)
+    }
+}
+
+/// An exact decimal exchange rate: one unit of `from` is worth `units / 10^scale` of `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExchangeRate {
+    pub date: Option<Date>,
+    pub from: Currency,
+    pub to: Currency,
+    units: u128,
+    scale: u32,
+}
+
+impl ExchangeRate {
+    pub fn new(
+        date: Option<Date>,
+        from: Currency,
+        to: Currency,
+        rate: &str,
+    ) -> Result<Self, String> {
+        let rate = rate.trim();
+        let invalid = || format!("`{}` is not a valid rate", rate);
+        let too_large = || format!("rate `{}` is too large", rate);
+        let (mantissa, exponent) = match rate.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => {
+                (mantissa, exponent.parse::<i32>().map_err(|_| invalid())?)
+            }
+            None => (rate, 0),
+        };
+        let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+            return Err(invalid());
+        }
+        let mut units = format!("{}{}", whole, fraction)
+            .parse::<u128>()
+            .map_err(|_| too_large())?;
+        // `1.5e-3` is 15 units at scale 4; a positive exponent beyond the fraction scales up.
+        let mut scale = i64::try_from(fraction.len()).map_err(|_| invalid())? - i64::from(exponent);
+        if scale < 0 {
+            units = u32::try_from(-scale)
+                .ok()
+                .and_then(|power| 10u128.checked_pow(power))
+                .and_then(|factor| units.checked_mul(factor))
+                .ok_or_else(too_large)?;
+            scale = 0;
+        }
+        // The denominator `10^scale` must fit in a `u128`.
+        if scale > 38 {
+            return Err(format!("rate `{}` has too many decimal places", rate));
+        }
+        if units == 0 {
+            return Err("rate must be greater than zero".to_string());
+        }
+        Ok(ExchangeRate {
+            date,
+            from,
+            to,
+            units,
+            scale: scale as u32,
+        })
+    }
+
+    /// The rate as a fraction `numerator / denominator`.
+    fn ratio(&self) -> (u128, u128) {
+        (self.units, 10u128.pow(self.scale))
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonRate {
+    #[serde(default)]
+    date: Option<Date>,
+    from: Currency,
+    to: Currency,
+    /// Kept as written so that a number such as `0.0000123` is not rounded through `f64`.
+    rate: Box<RawValue>,
+}
+
+/// A table of date-stamped exchange rates loaded from a local file.
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeRates {
+    rates: Vec<ExchangeRate>,
+}
+
+impl ExchangeRates {
+    /// Loads a `.json` file as JSON and anything else as CSV.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConvertError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&text)
+        } else {
+            Self::from_csv(&text)
+        }
+    }
+
+    /// Reads `date,from,to,rate` lines; a header line and blank lines are skipped and the date
+    /// may be left empty for rates that are always valid.
+    pub fn from_csv(text: &str) -> Result<Self, ConvertError> {
+        let mut rates = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || (index == 0 && line.starts_with("date")) {
+                continue;
+            }
+            let invalid = |message: String| ConvertError::InvalidRate {
+                line: index + 1,
+                message,
+            };
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [date, from, to, rate] = fields[..] else {
+                return Err(invalid(format!(
+                    "expected 4 fields `date,from,to,rate`, found {}",
+                    fields.len()
+                )));
+            };
+            let date = if date.is_empty() {
+                None
+            } else {
+                Some(
+                    date.parse()
+                        .map_err(|e: DateError| invalid(e.to_string()))?,
+                )
+            };
+            let from = from
+                .parse()
+                .map_err(|e: PriceError| invalid(e.to_string()))?;
+            let to = to.parse().map_err(|e: PriceError| invalid(e.to_string()))?;
+            rates.push(ExchangeRate::new(date, from, to, rate).map_err(invalid)?);
+        }
+        Ok(ExchangeRates { rates })
+    }
+
+    /// Reads a list of `{"date": "2024-01-31", "from": "USD", "to": "UAH", "rate": 41.25}`. The
+    /// rate may also be given as a string, e.g. `"41.25"`; either way it is read exactly as written.
+    pub fn from_json(text: &str) -> Result<Self, ConvertError> {
+        let entries: Vec<JsonRate> = serde_json::from_str(text)?;
+        let rates = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let rate = match entry.rate.get() {
+                    quoted if quoted.starts_with('"') => serde_json::from_str(quoted)?,
+                    number => number.to_string(),
+                };
+                ExchangeRate::new(entry.date, entry.from, entry.to, &rate).map_err(|message| {
+                    ConvertError::InvalidRate {
+                        line: index + 1,
+                        message,
+                    }
+                })
+            })
+            .collect::<Result<_, ConvertError>>()?;
+        Ok(ExchangeRates { rates })
+    }
+
+    pub fn rates(&self) -> &[ExchangeRate] {
+        &self.rates
+    }
+
+    /// The newest rate valid on `date` (or the newest overall), using the inverse of a
+    /// `to -> from` rate when no direct one exists.
+    fn ratio(&self, from: Currency, to: Currency, date: Option<Date>) -> Option<(u128, u128)> {
+        if from == to {
+            return Some((1, 1));
+        }
+        let newest = |from: Currency, to: Currency| {
+            self.rates
+                .iter()
+                .filter(|rate| rate.from == from && rate.to == to)
+                .filter(|rate| match (date, rate.date) {
+                    (Some(date), Some(valid_from)) => valid_from <= date,
+                    _ => true,
+                })
+                .max_by_key(|rate| rate.date)
+        };
+        newest(from, to).map(ExchangeRate::ratio).or_else(|| {
+            newest(to, from).map(|rate| {
+                let (numerator, denominator) = rate.ratio();
+                (denominator, numerator)
+            })
+        })
+    }
+
+    pub fn convert(
+        &self,
+        price: Price,
+        to: Currency,
+        date: Option<Date>,
+        rounding: Rounding,
+    ) -> Result<Price, ConvertError> {
+        let from = price.currency();
+        let (rate_numerator, rate_denominator) = self
+            .ratio(from, to, date)
+            .ok_or(ConvertError::MissingRate { from, to })?;
+
+        // target minor units = amount * rate * 10^target_exponent / 10^source_exponent
+        let numerator = u128::from(price.minor_units())
+            .checked_mul(rate_numerator)
+            .and_then(|n| n.checked_mul(10u128.pow(u32::from(to.exponent()))))
+            .ok_or(ConvertError::Overflow)?;
+        let denominator = rate_denominator
+            .checked_mul(10u128.pow(u32::from(from.exponent())))
+            .ok_or(ConvertError::Overflow)?;
+
+        let minor_units = u64::try_from(rounding.divide(numerator, denominator))
+            .map_err(|_| ConvertError::Overflow)?;
+        Ok(Price::new(minor_units, to))
+    }
+}
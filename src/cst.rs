@@ -0,0 +1,344 @@
+//! A lossless concrete syntax tree over a catalog.
+//!
+//! Unlike [`Book`], which keeps only the parsed values, the tree keeps every byte of the source:
+//! spacing inside fields, blank lines between books and anything else the grammar skips.
+//! Tools can change a single field and write the file back with only that field changed.
+
+use crate::error::{self, ParseError};
+use crate::{Book, Grammar, Rule};
+use pest::iterators::Pair;
+use pest::Parser;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxTree {
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    /// Text between entries, such as blank lines.
+    Trivia(String),
+    Entry(Entry),
+}
+
+/// One `Book N:` entry, made of its title line, its fields and any text between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    fields: Vec<Field>,
+}
+
+/// A single field or comment line of an entry, including its trailing newline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// The grammar rule that matched the field, or `None` for text between fields.
+    rule: Option<Rule>,
+    text: String,
+    /// Byte offset of the comment within `text`, if the line has one.
+    comment: Option<usize>,
+}
+
+impl SyntaxTree {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        if input.trim().is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+        let catalog = Grammar::parse(Rule::catalog, input)
+            .map_err(|error| ParseError::from_pest(error, input))?
+            .next()
+            .ok_or(ParseError::EmptyInput)?;
+
+        let mut items = Vec::new();
+        let mut cursor = 0;
+        for book in find_books(catalog) {
+            let span = book.as_span();
+            push_trivia(&mut items, &input[cursor..span.start()]);
+            items.push(Item::Entry(Entry::from_pair(book, input)));
+            cursor = span.end();
+        }
+        push_trivia(&mut items, &input[cursor..]);
+
+        Ok(SyntaxTree { items })
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Entry(entry) => Some(entry),
+            Item::Trivia(_) => None,
+        })
+    }
+
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = &mut Entry> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Entry(entry) => Some(entry),
+            Item::Trivia(_) => None,
+        })
+    }
+
+    /// Finds the entry whose header carries `id`, e.g. 3 for `Book 3:`.
+    pub fn entry_mut(&mut self, id: u64) -> Option<&mut Entry> {
+        self.entries_mut().find(|entry| entry.id() == Some(id))
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for item in &self.items {
+            match item {
+                Item::Trivia(text) => f.write_str(text)?,
+                Item::Entry(entry) => write!(f, "{}", entry)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Entry {
+    /// Parses the text of one entry, which must also hold a valid [`Book`].
+    fn parse(text: &str) -> Result<Self, ParseError> {
+        let pairs = Grammar::parse(Rule::entry, text)
+            .map_err(|error| ParseError::from_pest(error, text))?;
+        let book = pairs
+            .flat_map(find_books)
+            .next()
+            .ok_or(ParseError::EmptyInput)?;
+        Book::from_pair(book.clone())?;
+        Ok(Entry::from_pair(book, text))
+    }
+
+    fn from_pair(book: Pair<Rule>, input: &str) -> Self {
+        let mut fields: Vec<Field> = Vec::new();
+        let mut cursor = book.as_span().start();
+        let end = book.as_span().end();
+
+        for child in book.into_inner() {
+            let span = child.as_span();
+            let rule = child.as_rule();
+            let rule = (rule == Rule::comment_line || error::is_field(rule)).then_some(rule);
+            if rule.is_none() {
+                continue;
+            }
+            let comment = child
+                .clone()
+                .into_inner()
+                .find(|inner| inner.as_rule() == Rule::comment)
+                .map(|comment| comment.as_span().start() - span.start());
+            push_field(&mut fields, None, &input[cursor..span.start()], None);
+            push_field(&mut fields, rule, span.as_str(), comment);
+            cursor = span.end();
+        }
+        push_field(&mut fields, None, &input[cursor..end], None);
+
+        Entry { fields }
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// The number from the `Book N:` header.
+    pub fn id(&self) -> Option<u64> {
+        let header = self.field_by_rule(Rule::book_title)?;
+        header.key().strip_prefix("Book")?.trim().parse().ok()
+    }
+
+    /// Finds a field by the key written before its colon, e.g. `"Price"`.
+    pub fn field(&self, key: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|field| field.is_field() && field.key() == key)
+    }
+
+    /// Every field with `key`, in document order, e.g. each `Price:` line.
+    pub fn fields_named<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Field> + 'a {
+        self.fields
+            .iter()
+            .filter(move |field| field.is_field() && field.key() == key)
+    }
+
+    /// The comment lines of the entry, in document order.
+    pub fn comments(&self) -> impl Iterator<Item = &Field> {
+        self.fields
+            .iter()
+            .filter(|field| field.rule == Some(Rule::comment_line))
+    }
+
+    fn field_by_rule(&self, rule: Rule) -> Option<&Field> {
+        self.fields.iter().find(|field| field.rule == Some(rule))
+    }
+
+    /// Replaces the value of `key`, keeping the key and the spacing around the value. A field
+    /// that does not exist yet is appended after the last field of the entry. The edited entry
+    /// must still parse, otherwise it is left unchanged.
+    pub fn set_field(&mut self, key: &str, value: &str) -> Result<(), ParseError> {
+        self.set_field_at(key, 0, value)
+    }
+
+    /// Like [`Entry::set_field`] for the field with `key` at `index` among those with that key,
+    /// e.g. 1 for the second `Price:` line. When there are no more than `index` such fields, a
+    /// new one is appended.
+    pub fn set_field_at(&mut self, key: &str, index: usize, value: &str) -> Result<(), ParseError> {
+        let mut fields = self.fields.clone();
+        if let Some(field) = fields
+            .iter_mut()
+            .filter(|field| field.is_field() && field.key() == key)
+            .nth(index)
+        {
+            field.text = field.with_value(value);
+        } else {
+            let position = fields
+                .iter()
+                .rposition(|field| field.is_field())
+                .map_or(fields.len(), |index| index + 1);
+            let text = format!("{}: {}\n", key, value);
+            fields.insert(
+                position,
+                Field {
+                    rule: None,
+                    text,
+                    comment: None,
+                },
+            );
+        }
+
+        let text: String = fields.iter().map(|field| field.text.as_str()).collect();
+        *self = Entry::parse(&text)?;
+        Ok(())
+    }
+
+    /// Removes the field with `key`, returning it if it was present. The entry must still parse
+    /// without it, otherwise it is left unchanged.
+    pub fn remove_field(&mut self, key: &str) -> Result<Option<Field>, ParseError> {
+        self.remove_field_at(key, 0)
+    }
+
+    /// Removes the field with `key` at `index` among those with that key.
+    pub fn remove_field_at(
+        &mut self,
+        key: &str,
+        index: usize,
+    ) -> Result<Option<Field>, ParseError> {
+        let Some(position) = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.is_field() && field.key() == key)
+            .nth(index)
+            .map(|(position, _)| position)
+        else {
+            return Ok(None);
+        };
+        let mut fields = self.fields.clone();
+        let removed = fields.remove(position);
+
+        let text: String = fields.iter().map(|field| field.text.as_str()).collect();
+        *self = Entry::parse(&text)?;
+        Ok(Some(removed))
+    }
+
+    /// Parses the entry's current text into a [`Book`].
+    pub fn to_book(&self) -> Result<Book, ParseError> {
+        Book::parse(&self.to_string())
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for field in &self.fields {
+            f.write_str(&field.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Field {
+    pub fn rule(&self) -> Option<Rule> {
+        self.rule
+    }
+
+    /// The exact source text of the field, including its newline.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn is_field(&self) -> bool {
+        self.rule.is_some() && self.rule != Some(Rule::comment_line)
+    }
+
+    pub fn key(&self) -> &str {
+        self.text.split_once(':').map_or("", |(key, _)| key.trim())
+    }
+
+    /// The text after the colon, without surrounding whitespace or a trailing comment.
+    pub fn value(&self) -> &str {
+        self.text[..self.value_end()]
+            .split_once(':')
+            .map_or("", |(_, value)| value.trim())
+    }
+
+    /// The comment on the line, such as `# reprint pending`.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.map(|start| self.text[start..].trim_end())
+    }
+
+    fn value_end(&self) -> usize {
+        self.comment.unwrap_or(self.text.len())
+    }
+
+    fn with_value(&self, value: &str) -> String {
+        let Some(colon) = self.text.find(':') else {
+            return self.text.clone();
+        };
+        let after_colon = &self.text[colon + 1..self.value_end()];
+        let leading = after_colon.len() - after_colon.trim_start_matches([' ', '\t']).len();
+        let trailing = after_colon.len() - after_colon.trim_end().len();
+
+        let prefix_end = colon + 1 + leading;
+        let suffix_start = (colon + 1 + after_colon.len() - trailing).max(prefix_end);
+        format!(
+            "{}{}{}",
+            &self.text[..prefix_end],
+            value,
+            &self.text[suffix_start..]
+        )
+    }
+}
+
+fn find_books(pair: Pair<Rule>) -> Vec<Pair<Rule>> {
+    if pair.as_rule() == Rule::book {
+        return vec![pair];
+    }
+    pair.into_inner().flat_map(find_books).collect()
+}
+
+fn push_trivia(items: &mut Vec<Item>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(Item::Trivia(previous)) = items.last_mut() {
+        previous.push_str(text);
+    } else {
+        items.push(Item::Trivia(text.to_string()));
+    }
+}
+
+fn push_field(fields: &mut Vec<Field>, rule: Option<Rule>, text: &str, comment: Option<usize>) {
+    if text.is_empty() {
+        return;
+    }
+    if rule.is_none() {
+        if let Some(previous) = fields.last_mut().filter(|field| field.rule.is_none()) {
+            previous.text.push_str(text);
+            return;
+        }
+    }
+    fields.push(Field {
+        rule,
+        text: text.to_string(),
+        comment,
+    });
+}
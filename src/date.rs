@@ -0,0 +1,368 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DateError {
+    #[error("`{0}` is not a date in YYYY-MM-DD form")]
+    Format(String),
+    #[error("month {0} is out of range 1-12")]
+    Month(u8),
+    #[error("day {day} is out of range for {year}-{month:02}")]
+    Day { year: i32, month: u8, day: u8 },
+    #[error("`{0}` is not a publication date such as 2016, March 2016, 2016-03-14 or c. 400 BCE")]
+    PublicationDate(String),
+    #[error("unknown month `{0}`")]
+    MonthName(String),
+    #[error("there is no year 0; 1 BCE is followed by 1 CE")]
+    YearZero,
+    #[error("a month or day can only be given for a year of the common era, not {0}")]
+    EraMonth(i32),
+}
+
+/// A calendar date in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Result<Self, DateError> {
+        if !(1..=12).contains(&month) {
+            return Err(DateError::Month(month));
+        }
+        if day == 0 || day > days_in_month(year, month) {
+            return Err(DateError::Day { year, month, day });
+        }
+        Ok(Date { year, month, day })
+    }
+
+    /// Today's date in UTC, taken from the system clock.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        Self::from_days_since_epoch((seconds / 86_400) as i64)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    // Howard Hinnant's `civil_from_days`.
+    fn from_days_since_epoch(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Date { year, month, day }
+    }
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let format_error = || DateError::Format(text.to_string());
+        let mut parts = text.trim().splitn(3, '-');
+        let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format_error());
+        };
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return Err(format_error());
+        }
+        if !is_digits(year) || !is_digits(month) || !is_digits(day) {
+            return Err(format_error());
+        }
+        Date::new(
+            year.parse().map_err(|_| format_error())?,
+            month.parse().map_err(|_| format_error())?,
+            day.parse().map_err(|_| format_error())?,
+        )
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// When a book was published: a year, a month or a single day, possibly approximate. Years
+/// before the common era are negative, so 400 BCE is -400 and there is no year 0. Dates order by
+/// year, then month, then day, with a year on its own before its months.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PublicationDate {
+    year: i32,
+    month: Option<u8>,
+    day: Option<u8>,
+    circa: bool,
+}
+
+impl PublicationDate {
+    pub fn from_year(year: i32) -> Result<Self, DateError> {
+        if year == 0 {
+            return Err(DateError::YearZero);
+        }
+        Ok(Self::year_unchecked(year))
+    }
+
+    /// Builds a year without rejecting 0; the year is checked when the book is built.
+    pub(crate) fn year_unchecked(year: i32) -> Self {
+        PublicationDate {
+            year,
+            ..Self::default()
+        }
+    }
+
+    pub fn from_year_month(year: i32, month: u8) -> Result<Self, DateError> {
+        if year <= 0 {
+            return Err(DateError::EraMonth(year));
+        }
+        if !(1..=12).contains(&month) {
+            return Err(DateError::Month(month));
+        }
+        Ok(PublicationDate {
+            year,
+            month: Some(month),
+            ..Self::default()
+        })
+    }
+
+    pub fn from_date(date: Date) -> Result<Self, DateError> {
+        if date.year <= 0 {
+            return Err(DateError::EraMonth(date.year));
+        }
+        Ok(PublicationDate {
+            year: date.year,
+            month: Some(date.month),
+            day: Some(date.day),
+            circa: false,
+        })
+    }
+
+    /// Marks the date as approximate, written with a `c.` prefix.
+    pub fn circa(self) -> Self {
+        PublicationDate {
+            circa: true,
+            ..self
+        }
+    }
+
+    /// Reads `2016`, `March 2016`, `2016-03`, `2016-03-14`, `400 BCE` or `-400`, each optionally
+    /// preceded by `c.`, `ca.` or `circa`. Months and days are only accepted for common-era years.
+    pub fn parse(text: &str) -> Result<Self, DateError> {
+        let invalid = || DateError::PublicationDate(text.to_string());
+        let mut rest = text.trim();
+        let mut circa = false;
+        for marker in ["circa", "ca.", "c."] {
+            if let Some(prefix) = rest.get(..marker.len()) {
+                if prefix.eq_ignore_ascii_case(marker) {
+                    rest = rest[marker.len()..].trim_start();
+                    circa = true;
+                    break;
+                }
+            }
+        }
+        let is_digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+
+        let date = if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            // March 2016
+            let (month, year) = rest.split_once([' ', '\t']).ok_or_else(invalid)?;
+            let year = year.trim();
+            if !is_digits(year) {
+                return Err(invalid());
+            }
+            let name = month.trim_end_matches('.').to_lowercase();
+            let month = MONTHS
+                .iter()
+                .position(|full| *full == name || (name.len() == 3 && full.starts_with(&name)))
+                .ok_or_else(|| DateError::MonthName(month.to_string()))?;
+            Self::from_year_month(year.parse().map_err(|_| invalid())?, month as u8 + 1)?
+        } else if rest.get(1..).is_some_and(|tail| tail.contains('-')) {
+            // 2016-03 or 2016-03-14
+            let parts: Vec<&str> = rest.split('-').collect();
+            if parts[0].len() != 4 || parts[1..].iter().any(|part| part.len() != 2) {
+                return Err(invalid());
+            }
+            if !parts.iter().all(|part| is_digits(part)) {
+                return Err(invalid());
+            }
+            let number = |part: &str| part.parse().map_err(|_| invalid());
+            match parts[..] {
+                [year, month] => Self::from_year_month(number(year)?, number(month)? as u8)?,
+                [year, month, day] => Self::from_date(Date::new(
+                    number(year)?,
+                    number(month)? as u8,
+                    number(day)? as u8,
+                )?)?,
+                _ => return Err(invalid()),
+            }
+        } else {
+            // 2016, 2016 CE, 400 BCE or -400
+            let (number, era) = match rest.split_once([' ', '\t']) {
+                Some((number, era)) => (number, Some(era.trim().to_ascii_uppercase())),
+                None => (rest, None),
+            };
+            let (negative, digits) = match number.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, number),
+            };
+            if !is_digits(digits) {
+                return Err(invalid());
+            }
+            let year: i32 = digits.parse().map_err(|_| invalid())?;
+            let bce = match era.as_deref() {
+                None => negative,
+                Some("BCE" | "BC") if !negative => true,
+                Some("CE" | "AD") if !negative => false,
+                Some(_) => return Err(invalid()),
+            };
+            Self::from_year(if bce { -year } else { year })?
+        };
+
+        Ok(PublicationDate { circa, ..date })
+    }
+
+    /// The year, negative before the common era.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> Option<u8> {
+        self.month
+    }
+
+    pub fn day(&self) -> Option<u8> {
+        self.day
+    }
+
+    pub fn is_circa(&self) -> bool {
+        self.circa
+    }
+
+    pub fn is_bce(&self) -> bool {
+        self.year < 0
+    }
+
+    /// Whether even the earliest day the date could stand for comes after `date`, e.g. for a book
+    /// that is not published yet.
+    pub fn is_after(&self, date: &Date) -> bool {
+        let earliest = (self.year, self.month.unwrap_or(1), self.day.unwrap_or(1));
+        earliest > (date.year, date.month, date.day)
+    }
+}
+
+impl fmt::Display for PublicationDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.circa {
+            f.write_str("c. ")?;
+        }
+        match (self.month, self.day) {
+            (Some(month), Some(day)) => write!(f, "{:04}-{:02}-{:02}", self.year, month, day),
+            (Some(month), None) => write!(f, "{:04}-{:02}", self.year, month),
+            _ if self.year < 0 => write!(f, "{} BCE", -self.year),
+            _ => write!(f, "{}", self.year),
+        }
+    }
+}
+
+impl FromStr for PublicationDate {
+    type Err = DateError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        PublicationDate::parse(text)
+    }
+}
+
+impl Serialize for PublicationDate {
+    /// A plain common-era year is written as a number, as `publication_year` always was; anything
+    /// more precise, approximate or before the common era is written as text.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.month.is_none() && !self.circa && self.year >= 0 {
+            serializer.serialize_i32(self.year)
+        } else {
+            serializer.collect_str(self)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PublicationDateRepr {
+    Year(i32),
+    Text(String),
+}
+
+impl<'de> Deserialize<'de> for PublicationDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match PublicationDateRepr::deserialize(deserializer)? {
+            PublicationDateRepr::Year(year) => PublicationDate::from_year(year),
+            PublicationDateRepr::Text(text) => text.parse(),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,229 @@
+use crate::error::{Location, ParseError, Warning};
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A compiler-style report about a span of the catalog source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub location: Option<Location>,
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn from_error(error: &ParseError) -> Self {
+        let message = match error {
+            ParseError::EmptyInput => "input is empty".to_string(),
+            ParseError::Syntax {
+                field, expected, ..
+            } => {
+                let mut message = match field {
+                    Some(field) => format!("syntax error in `{}`", field),
+                    None => "syntax error".to_string(),
+                };
+                if !expected.is_empty() {
+                    message.push_str(&format!(", expected {}", expected.join(" or ")));
+                }
+                message
+            }
+            ParseError::MissingField { field, .. } => format!("missing field `{}`", field),
+            ParseError::DuplicateField { field, first, .. } => format!(
+                "duplicate field `{}`, first defined on line {}",
+                field, first.line
+            ),
+            ParseError::UnknownField { field, .. } => format!("unknown field `{}`", field),
+            ParseError::InvalidValue {
+                field,
+                value,
+                reason,
+                ..
+            } => format!("invalid value `{}` for `{}`: {}", value, field, reason),
+        };
+
+        let hint = match error {
+            ParseError::EmptyInput => Some("a catalog must contain at least one book".to_string()),
+            ParseError::Syntax {
+                field, expected, ..
+            } => expected
+                .iter()
+                .filter(|rule| *rule != "NEWLINE")
+                .filter_map(|rule| hint_for(rule))
+                .chain(field.as_deref().and_then(hint_for))
+                .chain(expected.iter().filter_map(|rule| hint_for(rule)))
+                .next()
+                .map(str::to_string),
+            ParseError::DuplicateField { field, .. } if field == "price" => Some(
+                "two `Price:` lines may not share currency, format and validity dates".to_string(),
+            ),
+            ParseError::DuplicateField { .. } => {
+                Some("each field may appear only once per book".to_string())
+            }
+            ParseError::UnknownField { .. } => Some(
+                "extension fields are rejected by the current parse options; check the spelling of the key"
+                    .to_string(),
+            ),
+            ParseError::MissingField { field, .. } | ParseError::InvalidValue { field, .. } => {
+                hint_for(field).map(str::to_string)
+            }
+        };
+
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+            location: error.location().copied(),
+            hint,
+        }
+    }
+
+    pub fn from_warning(warning: &Warning) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: warning.message.clone(),
+            location: Some(warning.location),
+            hint: hint_for(&warning.field).map(str::to_string),
+        }
+    }
+
+    /// Renders the diagnostic with the offending source line and a caret under the span.
+    pub fn render(&self, file_name: &str, source: &str, color: bool) -> String {
+        let paint = |style: &str, text: &str| {
+            if color {
+                format!("{}{}{}", style, text, RESET)
+            } else {
+                text.to_string()
+            }
+        };
+
+        let (label, style) = match self.severity {
+            Severity::Error => ("error", RED),
+            Severity::Warning => ("warning", YELLOW),
+        };
+        let mut out = format!(
+            "{}{}\n",
+            paint(style, label),
+            paint(BOLD, &format!(": {}", self.message))
+        );
+
+        let Some(location) = self.location else {
+            out.push_str(&format!("{} {}\n", paint(BLUE, "-->"), file_name));
+            if let Some(hint) = &self.hint {
+                out.push_str(&format!("{} hint: {}\n", paint(BLUE, "="), hint));
+            }
+            return out;
+        };
+
+        let line_text = source.lines().nth(location.line - 1).unwrap_or_default();
+        let gutter = location.line.to_string();
+        let pad = " ".repeat(gutter.len());
+
+        // Keep tabs in the caret line so it lines up with the source line.
+        let prefix: String = line_text
+            .chars()
+            .take(location.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let span_text = source.get(location.start..location.end).unwrap_or_default();
+        let span_len = span_text
+            .lines()
+            .next()
+            .map(|first| first.chars().count())
+            .unwrap_or(0)
+            .max(1);
+
+        out.push_str(&format!(
+            "{}{} {}:{}:{}\n",
+            pad,
+            paint(BLUE, "-->"),
+            file_name,
+            location.line,
+            location.column
+        ));
+        out.push_str(&format!("{} {}\n", pad, paint(BLUE, "|")));
+        out.push_str(&format!(
+            "{} {} {}\n",
+            paint(BLUE, &gutter),
+            paint(BLUE, "|"),
+            line_text
+        ));
+        out.push_str(&format!(
+            "{} {} {}{}\n",
+            pad,
+            paint(BLUE, "|"),
+            prefix,
+            paint(style, &"^".repeat(span_len))
+        ));
+        if let Some(hint) = &self.hint {
+            out.push_str(&format!("{} {} hint: {}\n", pad, paint(BLUE, "="), hint));
+        }
+
+        out
+    }
+}
+
+/// Returns a human hint for a grammar rule or a book field name.
+pub fn hint_for(rule: &str) -> Option<&'static str> {
+    let hint = match rule {
+        "book_title" | "book" | "book_num" => {
+            "each book starts with a header like `Book 1: \"Title\"`"
+        }
+        "quoted_text" => "titles must be enclosed in double quotes, `“…”` or `«…»`",
+        "authors" | "list_of_authors" | "author" => {
+            "authors are a bracketed, comma-separated list, e.g. `Authors: [Jane Doe, John Roe]`"
+        }
+        "genres" | "list_of_genres" | "genre_item" => {
+            "genres are a bracketed, comma-separated list, e.g. `Genres: [Fiction, Drama]`"
+        }
+        "publication_year" | "publication_date" | "year" | "iso_date" | "month_year" => {
+            "publication date must be a year, month or ISO date, e.g. `Publication Year: 2016`, `March 2016`, `2016-03-14` or `c. 400 BCE`"
+        }
+        "rating" | "rating_value" | "rating_number" | "rating_scale" => {
+            "rating is a number from 0 to 10, a number out of a maximum or a percentage, e.g. `Rating: 8.5`, `4.5/5` or `87%`"
+        }
+        "ratings" | "list_of_ratings" | "source_rating" | "rating_source" | "vote_count" => {
+            "ratings are a list of a source and a rating with an optional vote count, e.g. `Ratings: [Goodreads 4.1/5 (12,000 votes), Internal 8/10]`"
+        }
+        "price" | "number" | "money" => {
+            "price must be a number followed by a currency code, e.g. `Price: 199.00 UAH`"
+        }
+        "discount" => "a discount gives both prices, e.g. `Price: was 249.00 UAH now 199.00 UAH`",
+        "price_format" | "valid_from" | "valid_until" | "price_date" => {
+            "a price may name its format and dates after the amount, e.g. `Price: 149.00 UAH for ebook from 2026-11-01 until 2026-11-30`"
+        }
+        "currency" => "currency code expected after price, e.g. `UAH`",
+        "isbn" | "isbn_value" => {
+            "ISBN must have 10 or 13 digits with a valid check digit, e.g. `ISBN: 978-0-306-40615-7`"
+        }
+        "publisher" => "publisher must not be empty, e.g. `Publisher: Ranok`",
+        "pages" | "pages_value" => "pages must be a positive whole number, e.g. `Pages: 320`",
+        "language" | "language_tag" => {
+            "language must be a BCP 47 tag, e.g. `Language: uk` or `Language: en-GB`"
+        }
+        "format" | "book_format" | "format_value" => {
+            "format must be hardcover, paperback, ebook or audiobook"
+        }
+        "edition" => "edition must not be empty, e.g. `Edition: 2nd revised`",
+        "series" | "series_name" | "series_index" => {
+            "series is a name followed by a volume number, e.g. `Series: \"The Expanse\" #3`"
+        }
+        "description" | "literal_block" | "fenced_block" => {
+            "a description is inline text, an indented block after `Description: |`, or lines between ``` fences"
+        }
+        "NEWLINE" => "each field must be on its own line",
+        "comment" | "comment_line" => {
+            "comments start with `#` or `//` and run to the end of the line"
+        }
+        _ => return None,
+    };
+    Some(hint)
+}
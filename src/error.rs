@@ -0,0 +1,313 @@
+use crate::Rule;
+use pest::error::{ErrorVariant, InputLocation, LineColLocation};
+use pest::Span;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Position of a problem in the parsed input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Location {
+    pub fn from_span(span: &Span) -> Self {
+        let (line, column) = span.start_pos().line_col();
+        Location {
+            line,
+            column,
+            start: span.start(),
+            end: span.end(),
+        }
+    }
+
+    /// Moves a location found in a slice of the input back into the coordinates of the whole input.
+    pub(crate) fn shifted(self, lines: usize, bytes: usize) -> Self {
+        Location {
+            line: self.line + lines,
+            column: self.column,
+            start: self.start + bytes,
+            end: self.end + bytes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ParseError {
+    #[error("input is empty")]
+    EmptyInput,
+
+    #[error("{}:{}: syntax error{}, expected {}", location.line, location.column, in_field(field), expected_list(expected))]
+    Syntax {
+        location: Location,
+        field: Option<String>,
+        expected: Vec<String>,
+    },
+
+    #[error("{}:{}: missing field `{field}`", location.line, location.column)]
+    MissingField { location: Location, field: String },
+
+    #[error("{}:{}: duplicate field `{field}`, first defined at {}:{}", location.line, location.column, first.line, first.column)]
+    DuplicateField {
+        location: Location,
+        field: String,
+        first: Location,
+    },
+
+    #[error("{}:{}: unknown field `{field}`", location.line, location.column)]
+    UnknownField { location: Location, field: String },
+
+    #[error("{}:{}: invalid value `{value}` for `{field}`: {reason}", location.line, location.column)]
+    InvalidValue {
+        location: Location,
+        field: String,
+        value: String,
+        reason: String,
+    },
+}
+
+impl ParseError {
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::EmptyInput => None,
+            ParseError::Syntax { location, .. }
+            | ParseError::MissingField { location, .. }
+            | ParseError::DuplicateField { location, .. }
+            | ParseError::UnknownField { location, .. }
+            | ParseError::InvalidValue { location, .. } => Some(location),
+        }
+    }
+
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            ParseError::EmptyInput => None,
+            ParseError::Syntax { field, .. } => field.as_deref(),
+            ParseError::MissingField { field, .. }
+            | ParseError::DuplicateField { field, .. }
+            | ParseError::UnknownField { field, .. }
+            | ParseError::InvalidValue { field, .. } => Some(field),
+        }
+    }
+
+    pub(crate) fn shifted(self, lines: usize, bytes: usize) -> Self {
+        match self {
+            ParseError::EmptyInput => ParseError::EmptyInput,
+            ParseError::Syntax {
+                location,
+                field,
+                expected,
+            } => ParseError::Syntax {
+                location: location.shifted(lines, bytes),
+                field,
+                expected,
+            },
+            ParseError::MissingField { location, field } => ParseError::MissingField {
+                location: location.shifted(lines, bytes),
+                field,
+            },
+            ParseError::DuplicateField {
+                location,
+                field,
+                first,
+            } => ParseError::DuplicateField {
+                location: location.shifted(lines, bytes),
+                field,
+                first: first.shifted(lines, bytes),
+            },
+            ParseError::UnknownField { location, field } => ParseError::UnknownField {
+                location: location.shifted(lines, bytes),
+                field,
+            },
+            ParseError::InvalidValue {
+                location,
+                field,
+                value,
+                reason,
+            } => ParseError::InvalidValue {
+                location: location.shifted(lines, bytes),
+                field,
+                value,
+                reason,
+            },
+        }
+    }
+
+    pub(crate) fn invalid(span: &Span, field: Rule, reason: impl Into<String>) -> Self {
+        ParseError::InvalidValue {
+            location: Location::from_span(span),
+            field: field_name(field).to_string(),
+            value: span.as_str().trim().to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub(crate) fn missing(span: &Span, field: Rule) -> Self {
+        ParseError::MissingField {
+            location: Location::from_span(span),
+            field: field_name(field).to_string(),
+        }
+    }
+
+    /// Converts a pest error into a syntax error, naming the field the parser was inside of.
+    pub(crate) fn from_pest(error: pest::error::Error<Rule>, input: &str) -> Self {
+        let (start, end) = match error.location {
+            InputLocation::Pos(pos) => (pos, pos),
+            InputLocation::Span(span) => span,
+        };
+        let (line, column) = match error.line_col {
+            LineColLocation::Pos(line_col) | LineColLocation::Span(line_col, _) => line_col,
+        };
+        let positives = match &error.variant {
+            ErrorVariant::ParsingError { positives, .. } => positives.clone(),
+            ErrorVariant::CustomError { .. } => Vec::new(),
+        };
+
+        let field = positives
+            .iter()
+            .copied()
+            .find(|rule| is_field(*rule))
+            .or_else(|| field_for_line(input.lines().nth(line - 1).unwrap_or_default()))
+            .map(|rule| field_name(rule).to_string());
+
+        ParseError::Syntax {
+            location: Location {
+                line,
+                column,
+                start,
+                end,
+            },
+            field,
+            expected: positives
+                .into_iter()
+                .filter(|rule| *rule != Rule::WHITESPACE)
+                .map(|rule| format!("{:?}", rule))
+                .collect(),
+        }
+    }
+}
+
+/// A recoverable problem found while parsing, reported alongside the parsed value.
+#[derive(Debug, Clone, PartialEq, Serialize, Error)]
+#[error("{}:{}: warning: {message}", location.line, location.column)]
+pub struct Warning {
+    pub location: Location,
+    pub field: String,
+    pub message: String,
+}
+
+impl Warning {
+    pub(crate) fn defaulted(error: &ParseError, default: String) -> Self {
+        let reason = match error {
+            ParseError::InvalidValue { value, reason, .. } => {
+                format!("invalid value `{}`: {}", value, reason)
+            }
+            ParseError::MissingField { .. } => "field is missing".to_string(),
+            other => other.to_string(),
+        };
+        Warning {
+            location: error.location().copied().unwrap_or_default(),
+            field: error.field().unwrap_or("book").to_string(),
+            message: format!("{}, defaulted to {}", reason, default),
+        }
+    }
+
+    pub(crate) fn ignored(error: &ParseError) -> Self {
+        let reason = match error {
+            ParseError::DuplicateField { first, .. } => format!(
+                "duplicate field, first defined at {}:{}",
+                first.line, first.column
+            ),
+            other => other.to_string(),
+        };
+        Warning {
+            location: error.location().copied().unwrap_or_default(),
+            field: error.field().unwrap_or("book").to_string(),
+            message: format!("{}, ignored", reason),
+        }
+    }
+}
+
+pub(crate) fn field_name(rule: Rule) -> &'static str {
+    match rule {
+        Rule::book_title => "book_title",
+        Rule::book_num => "id",
+        Rule::list_of_authors => "authors",
+        Rule::list_of_genres => "genres",
+        Rule::publication_year => "publication_year",
+        Rule::rating => "rating",
+        Rule::list_of_ratings => "ratings",
+        Rule::price => "price",
+        Rule::isbn => "isbn",
+        Rule::publisher => "publisher",
+        Rule::pages => "pages",
+        Rule::language => "language",
+        Rule::book_format => "format",
+        Rule::edition => "edition",
+        Rule::series => "series",
+        Rule::description => "description",
+        Rule::extra_field => "extra",
+        _ => "book",
+    }
+}
+
+pub(crate) fn is_field(rule: Rule) -> bool {
+    matches!(
+        rule,
+        Rule::book_title
+            | Rule::list_of_authors
+            | Rule::list_of_genres
+            | Rule::publication_year
+            | Rule::rating
+            | Rule::list_of_ratings
+            | Rule::price
+            | Rule::isbn
+            | Rule::publisher
+            | Rule::pages
+            | Rule::language
+            | Rule::book_format
+            | Rule::edition
+            | Rule::series
+            | Rule::description
+            | Rule::extra_field
+    )
+}
+
+fn field_for_line(line: &str) -> Option<Rule> {
+    let key = line.split(':').next()?.trim();
+    match key {
+        "Authors" => Some(Rule::list_of_authors),
+        "Genres" => Some(Rule::list_of_genres),
+        "Publication Year" | "Publication Date" => Some(Rule::publication_year),
+        "Rating" => Some(Rule::rating),
+        "Ratings" => Some(Rule::list_of_ratings),
+        "Price" => Some(Rule::price),
+        "ISBN" => Some(Rule::isbn),
+        "Publisher" => Some(Rule::publisher),
+        "Pages" => Some(Rule::pages),
+        "Language" => Some(Rule::language),
+        "Format" => Some(Rule::book_format),
+        "Edition" => Some(Rule::edition),
+        "Series" => Some(Rule::series),
+        "Description" => Some(Rule::description),
+        _ if key.starts_with("Book") => Some(Rule::book_title),
+        _ => None,
+    }
+}
+
+fn in_field(field: &Option<String>) -> String {
+    match field {
+        Some(field) => format!(" in `{}`", field),
+        None => String::new(),
+    }
+}
+
+fn expected_list(expected: &[String]) -> String {
+    if expected.is_empty() {
+        "valid input".to_string()
+    } else {
+        expected.join(", ")
+    }
+}
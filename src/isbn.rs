@@ -0,0 +1,142 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IsbnError {
+    #[error("`{0}` has {1} digits, an ISBN has 10 or 13")]
+    Length(String, usize),
+    #[error("`{0}` contains characters other than digits and hyphens")]
+    Character(String),
+    #[error("`{0}` has an invalid check digit")]
+    Checksum(String),
+    #[error("`{0}` does not start with 978 or 979")]
+    Prefix(String),
+}
+
+/// An International Standard Book Number, stored as the 13 digits of its ISBN-13 form.
+/// ISBN-10 input is converted on parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Isbn {
+    digits: [u8; 13],
+}
+
+impl Isbn {
+    /// Parses an ISBN-10 or ISBN-13, ignoring hyphens and spaces and checking the check digit.
+    pub fn parse(text: &str) -> Result<Self, IsbnError> {
+        let compact: String = text.chars().filter(|c| !matches!(c, '-' | ' ')).collect();
+        match compact.len() {
+            10 => Self::from_isbn10(text, &compact),
+            13 => Self::from_isbn13(text, &compact),
+            length => Err(IsbnError::Length(text.to_string(), length)),
+        }
+    }
+
+    fn from_isbn10(text: &str, compact: &str) -> Result<Self, IsbnError> {
+        let mut values = [0u32; 10];
+        for (index, c) in compact.chars().enumerate() {
+            values[index] = match c {
+                '0'..='9' => c as u32 - '0' as u32,
+                'X' | 'x' if index == 9 => 10,
+                _ => return Err(IsbnError::Character(text.to_string())),
+            };
+        }
+        let sum: u32 = values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (10 - index as u32) * value)
+            .sum();
+        if !sum.is_multiple_of(11) {
+            return Err(IsbnError::Checksum(text.to_string()));
+        }
+
+        let mut digits = [9, 7, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        for (index, value) in values[..9].iter().enumerate() {
+            digits[index + 3] = *value as u8;
+        }
+        digits[12] = isbn13_check_digit(&digits[..12]);
+        Ok(Isbn { digits })
+    }
+
+    fn from_isbn13(text: &str, compact: &str) -> Result<Self, IsbnError> {
+        let mut digits = [0u8; 13];
+        for (index, c) in compact.chars().enumerate() {
+            digits[index] =
+                c.to_digit(10)
+                    .ok_or_else(|| IsbnError::Character(text.to_string()))? as u8;
+        }
+        if digits[..3] != [9, 7, 8] && digits[..3] != [9, 7, 9] {
+            return Err(IsbnError::Prefix(text.to_string()));
+        }
+        if isbn13_check_digit(&digits[..12]) != digits[12] {
+            return Err(IsbnError::Checksum(text.to_string()));
+        }
+        Ok(Isbn { digits })
+    }
+
+    /// The 13-digit form without hyphens, e.g. `9780306406157`.
+    pub fn to_isbn13(&self) -> String {
+        self.digits
+            .iter()
+            .map(|digit| char::from(b'0' + digit))
+            .collect()
+    }
+
+    /// The 10-digit form, which only exists for ISBNs starting with 978.
+    pub fn to_isbn10(&self) -> Option<String> {
+        if self.digits[..3] != [9, 7, 8] {
+            return None;
+        }
+        let body = &self.digits[3..12];
+        let sum: u32 = body
+            .iter()
+            .enumerate()
+            .map(|(index, digit)| (10 - index as u32) * u32::from(*digit))
+            .sum();
+        let check = match (11 - sum % 11) % 11 {
+            10 => 'X',
+            digit => char::from(b'0' + digit as u8),
+        };
+        let mut isbn10: String = body.iter().map(|digit| char::from(b'0' + digit)).collect();
+        isbn10.push(check);
+        Some(isbn10)
+    }
+}
+
+fn isbn13_check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .enumerate()
+        .map(|(index, digit)| u32::from(*digit) * if index % 2 == 0 { 1 } else { 3 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+impl fmt::Display for Isbn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_isbn13())
+    }
+}
+
+impl FromStr for Isbn {
+    type Err = IsbnError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Isbn::parse(text)
+    }
+}
+
+impl Serialize for Isbn {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Isbn {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
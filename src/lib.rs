@@ -1,102 +1,1223 @@
 use pest::iterators::Pair;
+use pest::Parser;
 use pest_derive::Parser;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub mod builder;
+pub mod contributor;
+pub mod convert;
+pub mod cst;
+pub mod date;
+pub mod diagnostics;
+pub mod error;
+pub mod extra;
+pub mod isbn;
+pub mod metadata;
+pub mod options;
+pub mod price;
+mod quote;
+pub mod rating;
+pub mod series;
+pub mod taxonomy;
+mod writer;
+
+pub use builder::{BookBuilder, ValidationError};
+pub use contributor::{Contributor, Role};
+pub use convert::{ConvertError, ExchangeRates, Rounding};
+pub use cst::SyntaxTree;
+pub use date::{Date, DateError, PublicationDate};
+pub use diagnostics::Diagnostic;
+pub use error::{Location, ParseError, Warning};
+pub use extra::ExtraFields;
+pub use isbn::Isbn;
+pub use metadata::{Format, LanguageTag};
+pub use options::{JsonOptions, Mode, ParseOptions, Parsed};
+pub use price::{Currency, Price, PriceError, PriceTag};
+pub use rating::{Rating, RatingError, RatingScale, SourceRating};
+pub use series::Series;
+pub use taxonomy::{GenreTaxonomy, TaxonomyError};
+
+use options::Reporter;
+use price::PriceList;
 
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
 pub struct Grammar;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Book {
+    id: u64,
     book_title: String,
-    authors: Vec<String>,
+    authors: Vec<Contributor>,
     genres: Vec<String>,
-    publication_year: u16,
-    rating: f32,
-    price: String,
+    publication_year: PublicationDate,
+    rating: Option<Rating>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ratings: Vec<SourceRating>,
+    #[serde(flatten)]
+    prices: PriceList,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    isbn: Option<Isbn>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    publisher: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pages: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language: Option<LanguageTag>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    format: Option<Format>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    edition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    series: Option<Series>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "ExtraFields::is_empty")]
+    extra: ExtraFields,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
 }
 
 impl Book {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        let mut book_title = String::new();
-        let mut authors = Vec::new();
-        let mut genres = Vec::new();
-        let mut publication_year = 0;
-        let mut rating = 0.0;
-        let mut price = "0 UAH".to_string();
+    pub fn builder() -> BookBuilder {
+        BookBuilder::new()
+    }
+
+    /// Parses a single book entry such as the one in `src/input.txt`.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        if input.trim().is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+        let entry = Grammar::parse(Rule::entry, input)
+            .map_err(|error| ParseError::from_pest(error, input))?
+            .next()
+            .ok_or(ParseError::EmptyInput)?;
+        let notes = trailing_notes(&entry);
+        let book = value_of(entry, Rule::book).ok_or(ParseError::EmptyInput)?;
+        let mut book = Self::from_pair(book)?;
+        book.notes.extend(notes);
+        Ok(book)
+    }
+
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, ParseError> {
+        Ok(Self::from_pair_with(pair, &ParseOptions::strict())?.value)
+    }
+
+    pub fn from_pair_with(
+        pair: Pair<Rule>,
+        options: &ParseOptions,
+    ) -> Result<Parsed<Self>, ParseError> {
+        let mut reporter = Reporter::new(options);
+        let book = Self::build(pair, &mut reporter)?;
+        Ok(Parsed {
+            value: book,
+            warnings: reporter.warnings,
+        })
+    }
+
+    /// The number from the `Book N:` header.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn book_title(&self) -> &str {
+        &self.book_title
+    }
+
+    /// Everyone listed under `Authors:`, including translators, editors and illustrators.
+    pub fn authors(&self) -> &[Contributor] {
+        &self.authors
+    }
+
+    pub fn genres(&self) -> &[String] {
+        &self.genres
+    }
+
+    /// The year of publication, negative before the common era.
+    pub fn publication_year(&self) -> i32 {
+        self.publication_year.year()
+    }
+
+    pub fn publication_date(&self) -> &PublicationDate {
+        &self.publication_year
+    }
+
+    /// The rating on the 0–10 scale, whatever scale it was written on.
+    pub fn rating(&self) -> Option<f32> {
+        self.rating.map(|rating| rating.normalized())
+    }
+
+    /// The rating as written, together with its scale.
+    pub fn scaled_rating(&self) -> Option<Rating> {
+        self.rating
+    }
+
+    /// Ratings from other sources, in the order they were listed.
+    pub fn ratings(&self) -> &[SourceRating] {
+        &self.ratings
+    }
+
+    /// The mean of the `Ratings:` list on the 0–10 scale, each rating weighted by its votes and one
+    /// without a vote count counting as a single vote. Falls back to `Rating:` when the list is
+    /// empty or has no votes at all. Use [`Book::aggregate_rating_with`] to give ratings without a
+    /// vote count more say, e.g. an in-house rating next to thousands of reader votes.
+    pub fn aggregate_rating(&self) -> Option<f32> {
+        self.aggregate_rating_with(1.0)
+    }
+
+    /// Like [`Book::aggregate_rating`], with a rating that has no vote count weighing as much as
+    /// `unvoted_weight` votes.
+    pub fn aggregate_rating_with(&self, unvoted_weight: f64) -> Option<f32> {
+        // Summed in floating point, since vote counts can add up past `u64::MAX`.
+        let (total, weight) =
+            self.ratings
+                .iter()
+                .fold((0.0f64, 0.0f64), |(total, weight), rating| {
+                    let votes = rating.votes().map_or(unvoted_weight, |votes| votes as f64);
+                    (
+                        total + f64::from(rating.rating().normalized()) * votes,
+                        weight + votes,
+                    )
+                });
+        if weight <= 0.0 {
+            return self.rating();
+        }
+        Some((total / weight) as f32)
+    }
+
+    /// The amount of the first `Price:` line.
+    pub fn price(&self) -> Option<&Price> {
+        self.prices.first()
+    }
+
+    /// Every `Price:` line in the order it was written.
+    pub fn prices(&self) -> &[PriceTag] {
+        &self.prices.0
+    }
+
+    /// The price to pay in `currency` on `date`, or `None` when no line applies. When `format` is
+    /// given, lines for other formats are left out and a line for that format wins over one for
+    /// every format. Of the rest, the line whose validity started last wins, so a sale or a later
+    /// price change overrides the regular price, and ties go to the lowest amount.
+    pub fn effective_price(
+        &self,
+        currency: Currency,
+        format: Option<Format>,
+        date: Date,
+    ) -> Option<&PriceTag> {
+        self.prices
+            .0
+            .iter()
+            .filter(|tag| tag.price().currency() == currency && tag.is_valid_on(date))
+            .filter(|tag| format.is_none() || tag.format().is_none() || tag.format() == format)
+            .min_by_key(|tag| {
+                (
+                    format.is_some() && tag.format().is_none(),
+                    std::cmp::Reverse(tag.valid_from()),
+                    tag.price().minor_units(),
+                )
+            })
+    }
+
+    pub fn isbn(&self) -> Option<&Isbn> {
+        self.isbn.as_ref()
+    }
+
+    pub fn publisher(&self) -> Option<&str> {
+        self.publisher.as_deref()
+    }
+
+    pub fn pages(&self) -> Option<u32> {
+        self.pages
+    }
+
+    pub fn language(&self) -> Option<&LanguageTag> {
+        self.language.as_ref()
+    }
+
+    pub fn format(&self) -> Option<Format> {
+        self.format
+    }
+
+    pub fn edition(&self) -> Option<&str> {
+        self.edition.as_deref()
+    }
+
+    /// The series from `Series:` and the book's volume number in it.
+    pub fn series(&self) -> Option<&Series> {
+        self.series.as_ref()
+    }
+
+    /// The blurb from `Description:`, with paragraphs separated by blank lines.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// `Key: value` lines the grammar has no rule for, in the order they were written.
+    pub fn extra(&self) -> &ExtraFields {
+        &self.extra
+    }
+
+    /// Comments written in or just before the entry, including their `#` or `//` marker.
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    /// Converts the book's first price into `currency` using the rates valid on `date`.
+    /// Returns `None` when the book has no price.
+    pub fn price_in(
+        &self,
+        currency: Currency,
+        rates: &ExchangeRates,
+        date: Option<Date>,
+        rounding: Rounding,
+    ) -> Option<Result<Price, ConvertError>> {
+        self.price()
+            .map(|price| rates.convert(*price, currency, date, rounding))
+    }
+
+    /// Serializes the book to JSON, applying the compatibility switches in `options`.
+    pub fn to_json(&self, options: &JsonOptions) -> serde_json::Result<serde_json::Value> {
+        let mut value = serde_json::to_value(self)?;
+        if options.plain_authors {
+            value["authors"] = self
+                .authors
+                .iter()
+                .map(|author| serde_json::Value::from(author.to_string()))
+                .collect();
+        }
+        if options.legacy_price {
+            value["price"] = match self.price() {
+                Some(price) => price.to_string().into(),
+                None => serde_json::Value::Null,
+            };
+        }
+        Ok(value)
+    }
+
+    fn build(pair: Pair<Rule>, reporter: &mut Reporter<'_>) -> Result<Self, ParseError> {
+        let book_span = pair.as_span();
+        let mut id = None;
+        let mut book_title = None;
+        let mut authors = None;
+        let mut genres = None;
+        let mut publication_year = None;
+        let mut rating = None;
+        let mut ratings = None;
+        let mut prices: Vec<(PriceTag, Location)> = Vec::new();
+        let mut isbn = None;
+        let mut publisher = None;
+        let mut pages = None;
+        let mut language = None;
+        let mut format = None;
+        let mut edition = None;
+        let mut series = None;
+        let mut description = None;
+        let mut extra = ExtraFields::new();
+        let mut notes = Vec::new();
+        let mut seen: Vec<(Rule, String, Location)> = Vec::new();
 
         for inner_pair in pair.into_inner() {
-            match inner_pair.as_rule() {
+            let rule = inner_pair.as_rule();
+            notes.extend(comment_of(&inner_pair));
+            // A book may have several prices; two lines for the same offer are caught once parsed.
+            if error::is_field(rule) && rule != Rule::price {
+                let location = Location::from_span(&inner_pair.as_span());
+                let name = match rule {
+                    Rule::extra_field => extra_key(&inner_pair),
+                    _ => error::field_name(rule).to_string(),
+                };
+                if let Some((_, _, first)) = seen
+                    .iter()
+                    .find(|(seen_rule, seen_name, _)| *seen_rule == rule && *seen_name == name)
+                {
+                    reporter.ignore(ParseError::DuplicateField {
+                        location,
+                        field: name,
+                        first: *first,
+                    })?;
+                    continue;
+                }
+                if rule == Rule::extra_field && reporter.reject_unknown_fields {
+                    reporter.ignore(ParseError::UnknownField {
+                        location,
+                        field: name,
+                    })?;
+                    continue;
+                }
+                seen.push((rule, name, location));
+            }
+
+            match rule {
                 Rule::book_title => {
-                    let mut inner = inner_pair.into_inner();
-                    inner.next();
-                    inner.next();
-                    if let Some(title) = inner.next() {
-                        book_title = title.as_str().trim_matches('"').to_string();
-                    }
+                    id = Some(match parse_book_num(inner_pair.clone()) {
+                        Ok(id) => id,
+                        Err(e) => reporter.recover(e, 0)?,
+                    });
+                    book_title = Some(match parse_title(inner_pair) {
+                        Ok(title) => title,
+                        Err(e) => reporter.recover(e, String::new())?,
+                    });
                 }
                 Rule::list_of_authors => {
-                    authors = inner_pair
-                        .into_inner()
-                        .filter_map(|author| {
-                            let name = author.as_str().trim();
-                            if !name.is_empty() {
-                                Some(name.to_string())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
+                    authors = Some(match parse_authors(inner_pair) {
+                        Ok(authors) => authors,
+                        Err(e) => reporter.recover(e, Vec::new())?,
+                    });
                 }
                 Rule::list_of_genres => {
-                    genres = inner_pair
-                        .into_inner()
-                        .filter_map(|genre| {
-                            let genre_name = genre.as_str().trim();
-                            if !genre_name.is_empty() {
-                                Some(genre_name.to_string())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
+                    genres = Some(match parse_genres(inner_pair, reporter) {
+                        Ok(genres) => genres,
+                        Err(e) => reporter.recover(e, Vec::new())?,
+                    });
                 }
                 Rule::publication_year => {
-                    let mut inner = inner_pair.into_inner();
-                    inner.next();
-                    if let Ok(year) = inner.as_str().trim().parse::<u16>() {
-                        publication_year = year;
+                    let location = Location::from_span(&inner_pair.as_span());
+                    let date = match parse_year(inner_pair) {
+                        Ok(date) => date,
+                        Err(e) => PublicationDate::year_unchecked(reporter.recover(e, 0)?),
+                    };
+                    if date.is_after(&reporter.today) {
+                        reporter.warn(Warning {
+                            location,
+                            field: error::field_name(rule).to_string(),
+                            message: format!("publication date {} is in the future", date),
+                        });
                     }
+                    publication_year = Some(date);
                 }
                 Rule::rating => {
-                    let mut inner = inner_pair.into_inner();
-                    inner.next();
-                    if let Ok(rating_value) = inner.as_str().trim().parse::<f32>() {
-                        rating = rating_value;
-                    }
+                    rating = match parse_value(inner_pair, Rule::rating_value) {
+                        Ok(rating) => Some(rating),
+                        Err(e) => reporter.recover(e, None)?,
+                    };
+                }
+                Rule::list_of_ratings => {
+                    ratings = Some(match parse_ratings(inner_pair) {
+                        Ok(ratings) => ratings,
+                        Err(e) => reporter.recover(e, Vec::new())?,
+                    });
                 }
                 Rule::price => {
-                    let mut inner = inner_pair.into_inner();
-                    inner.next();
-                    if let Some(price_value) = inner.next() {
-                        price = price_value.as_str().trim().to_string();
-                    }
-                    inner.next();
-                    if let Some(currency) = inner.next() {
-                        price.push_str(&format!(" {}", currency.as_str().trim()));
+                    let location = Location::from_span(&inner_pair.as_span());
+                    let tag = match parse_price(inner_pair) {
+                        Ok(tag) => tag,
+                        Err(e) => {
+                            reporter.ignore(e)?;
+                            continue;
+                        }
+                    };
+                    if let Some((_, first)) = prices.iter().find(|(seen, _)| seen.same_offer(&tag))
+                    {
+                        reporter.ignore(ParseError::DuplicateField {
+                            location,
+                            field: error::field_name(rule).to_string(),
+                            first: *first,
+                        })?;
+                        continue;
                     }
+                    prices.push((tag, location));
+                }
+                Rule::isbn => {
+                    isbn = match parse_value(inner_pair, Rule::isbn_value) {
+                        Ok(isbn) => Some(isbn),
+                        Err(e) => reporter.recover(e, None)?,
+                    };
+                }
+                Rule::publisher => {
+                    publisher = match parse_text(inner_pair) {
+                        Ok(publisher) => Some(publisher),
+                        Err(e) => reporter.recover(e, None)?,
+                    };
+                }
+                Rule::pages => {
+                    pages = match parse_pages(inner_pair) {
+                        Ok(pages) => Some(pages),
+                        Err(e) => reporter.recover(e, None)?,
+                    };
+                }
+                Rule::language => {
+                    language = match parse_value(inner_pair, Rule::language_tag) {
+                        Ok(language) => Some(language),
+                        Err(e) => reporter.recover(e, None)?,
+                    };
+                }
+                Rule::book_format => {
+                    format = match parse_value(inner_pair, Rule::format_value) {
+                        Ok(format) => Some(format),
+                        Err(e) => reporter.recover(e, None)?,
+                    };
+                }
+                Rule::edition => {
+                    edition = match parse_text(inner_pair) {
+                        Ok(edition) => Some(edition),
+                        Err(e) => reporter.recover(e, None)?,
+                    };
+                }
+                Rule::series => {
+                    series = match parse_series(inner_pair) {
+                        Ok(series) => Some(series),
+                        Err(e) => reporter.recover(e, None)?,
+                    };
+                }
+                Rule::description => {
+                    description = match parse_description(inner_pair) {
+                        Ok(description) => Some(description),
+                        Err(e) => reporter.recover(e, None)?,
+                    };
+                }
+                Rule::extra_field => {
+                    let key = extra_key(&inner_pair);
+                    let value = match parse_extra_value(inner_pair) {
+                        Ok(value) => value,
+                        Err(e) => reporter.recover(e, String::new())?,
+                    };
+                    extra.insert(key, value);
                 }
                 _ => {}
             }
         }
 
-        Book {
-            book_title,
-            authors,
-            genres,
-            publication_year,
+        let missing = |field: Rule| ParseError::missing(&book_span, field);
+
+        Ok(Book {
+            id: match id {
+                Some(id) => id,
+                None => reporter.recover(missing(Rule::book_num), 0)?,
+            },
+            book_title: match book_title {
+                Some(title) => title,
+                None => reporter.recover(missing(Rule::book_title), String::new())?,
+            },
+            authors: match authors {
+                Some(authors) => authors,
+                None => reporter.recover(missing(Rule::list_of_authors), Vec::new())?,
+            },
+            genres: match genres {
+                Some(genres) => genres,
+                None => reporter.recover(missing(Rule::list_of_genres), Vec::new())?,
+            },
+            publication_year: match publication_year {
+                Some(year) => year,
+                None => PublicationDate::year_unchecked(
+                    reporter.recover(missing(Rule::publication_year), 0)?,
+                ),
+            },
             rating,
-            price,
+            ratings: ratings.unwrap_or_default(),
+            prices: PriceList(prices.into_iter().map(|(tag, _)| tag).collect()),
+            isbn,
+            publisher,
+            pages,
+            language,
+            format,
+            edition,
+            series,
+            description,
+            extra,
+            notes,
+        })
+    }
+}
+
+impl FromStr for Book {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Book::parse(input)
+    }
+}
+
+impl TryFrom<&str> for Book {
+    type Error = ParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        Book::parse(input)
+    }
+}
+
+fn parse_book_num(pair: Pair<Rule>) -> Result<u64, ParseError> {
+    let span = pair.as_span();
+    let number =
+        value_of(pair, Rule::book_num).ok_or_else(|| ParseError::missing(&span, Rule::book_num))?;
+    number.as_str().parse::<u64>().map_err(|_| {
+        ParseError::invalid(
+            &number.as_span(),
+            Rule::book_num,
+            format!("book number must be between 0 and {}", u64::MAX),
+        )
+    })
+}
+
+fn parse_title(pair: Pair<Rule>) -> Result<String, ParseError> {
+    let span = pair.as_span();
+    let title = value_of(pair, Rule::quoted_text)
+        .ok_or_else(|| ParseError::missing(&span, Rule::book_title))?;
+    let text = quote::unquote(title.as_str())
+        .map_err(|reason| ParseError::invalid(&title.as_span(), Rule::book_title, reason))?;
+    builder::validate_title(&text)
+        .map_err(|e| ParseError::invalid(&title.as_span(), Rule::book_title, e.message))?;
+    Ok(text)
+}
+
+fn parse_authors(pair: Pair<Rule>) -> Result<Vec<Contributor>, ParseError> {
+    let mut authors = Vec::new();
+    for item in pair
+        .into_inner()
+        .filter(|item| item.as_rule() == Rule::author)
+    {
+        let role = value_of(item.clone(), Rule::role).map(|role| role.as_str().parse());
+        if let Some(name) = item_text(item, Rule::list_of_authors)? {
+            // The grammar only accepts known roles, so parsing one cannot fail.
+            authors.push(Contributor::new(
+                name,
+                role.and_then(Result::ok).unwrap_or_default(),
+            ));
         }
     }
+    Ok(authors)
+}
+
+/// Reads the genres list. With a taxonomy, names and aliases it knows are replaced by the
+/// canonical genre and kept once, and the others are kept as written with a warning.
+fn parse_genres(pair: Pair<Rule>, reporter: &mut Reporter<'_>) -> Result<Vec<String>, ParseError> {
+    let rule = pair.as_rule();
+    let mut genres: Vec<String> = Vec::new();
+    for item in pair
+        .into_inner()
+        .filter(|item| item.as_rule() == Rule::genre_item)
+    {
+        let location = Location::from_span(&item.as_span());
+        let Some(genre) = item_text(item, rule)? else {
+            continue;
+        };
+        let Some(taxonomy) = reporter.taxonomy else {
+            genres.push(genre);
+            continue;
+        };
+        let genre = match taxonomy.canonical(&genre) {
+            Some(canonical) => canonical.to_string(),
+            None => {
+                reporter.warn(Warning {
+                    location,
+                    field: error::field_name(rule).to_string(),
+                    message: format!("unknown genre `{}`", genre),
+                });
+                genre
+            }
+        };
+        if !genres.contains(&genre) {
+            genres.push(genre);
+        }
+    }
+    Ok(genres)
+}
+
+/// The unquoted text of a list item, or `None` for a bare item that is only whitespace.
+fn item_text(item: Pair<Rule>, rule: Rule) -> Result<Option<String>, ParseError> {
+    if let Some(quoted) = value_of(item.clone(), Rule::quoted_text) {
+        return quote::unquote(quoted.as_str())
+            .map(Some)
+            .map_err(|reason| ParseError::invalid(&quoted.as_span(), rule, reason));
+    }
+    let text = value_of(item.clone(), Rule::author_name).unwrap_or(item);
+    let name = text.as_str().trim();
+    Ok((!name.is_empty()).then(|| name.to_string()))
+}
+
+fn parse_year(pair: Pair<Rule>) -> Result<PublicationDate, ParseError> {
+    let span = pair.as_span();
+    let value = value_of(pair, Rule::publication_date)
+        .ok_or_else(|| ParseError::missing(&span, Rule::publication_year))?;
+    let invalid =
+        |reason: String| ParseError::invalid(&value.as_span(), Rule::publication_year, reason);
+    let date: PublicationDate = value
+        .as_str()
+        .parse()
+        .map_err(|e: date::DateError| invalid(e.to_string()))?;
+    builder::validate_year(date.year()).map_err(|e| invalid(e.message))?;
+    Ok(date)
+}
+
+fn parse_ratings(pair: Pair<Rule>) -> Result<Vec<SourceRating>, ParseError> {
+    let rule = pair.as_rule();
+    pair.into_inner()
+        .filter(|item| item.as_rule() == Rule::source_rating)
+        .map(|item| {
+            let invalid = |value: &Pair<Rule>, reason: String| {
+                ParseError::invalid(&value.as_span(), rule, reason)
+            };
+            let source = match value_of(item.clone(), Rule::quoted_text) {
+                Some(quoted) => {
+                    quote::unquote(quoted.as_str()).map_err(|reason| invalid(&quoted, reason))?
+                }
+                None => value_of(item.clone(), Rule::rating_source)
+                    .map(|source| source.as_str().to_string())
+                    .unwrap_or_default(),
+            };
+            builder::validate_text("ratings", &source).map_err(|e| invalid(&item, e.message))?;
+            let value = value_of(item.clone(), Rule::rating_value)
+                .ok_or_else(|| ParseError::missing(&item.as_span(), rule))?;
+            let rating = value
+                .as_str()
+                .parse()
+                .map_err(|e: RatingError| invalid(&value, e.to_string()))?;
+            let votes = match value_of(item, Rule::vote_count) {
+                Some(count) => Some(
+                    count
+                        .as_str()
+                        .replace(',', "")
+                        .parse()
+                        .map_err(|e: std::num::ParseIntError| invalid(&count, e.to_string()))?,
+                ),
+                None => None,
+            };
+            Ok(SourceRating::new(source, rating, votes))
+        })
+        .collect()
+}
+
+fn parse_price(pair: Pair<Rule>) -> Result<PriceTag, ParseError> {
+    let span = pair.as_span();
+    let mut tag = None;
+    let mut was = None;
+    let mut format = None;
+    let mut valid_from = None;
+    let mut valid_until = None;
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::money => tag = Some(PriceTag::new(parse_money(inner)?)),
+            Rule::discount => {
+                let mut amounts = inner
+                    .into_inner()
+                    .filter(|amount| amount.as_rule() == Rule::money)
+                    .map(parse_money);
+                was = amounts.next().transpose()?;
+                tag = amounts.next().transpose()?.map(PriceTag::new);
+            }
+            Rule::price_format => {
+                format = Some(parse_part(inner, Rule::format_value, Rule::price)?)
+            }
+            Rule::valid_from => {
+                valid_from = Some(parse_part(inner, Rule::price_date, Rule::price)?)
+            }
+            Rule::valid_until => {
+                valid_until = Some(parse_part(inner, Rule::price_date, Rule::price)?)
+            }
+            _ => {}
+        }
+    }
+    let mut tag = tag.ok_or_else(|| ParseError::missing(&span, Rule::price))?;
+    if let Some(was) = was {
+        tag = tag.with_was(was);
+    }
+    if let Some(format) = format {
+        tag = tag.with_format(format);
+    }
+    if let Some(date) = valid_from {
+        tag = tag.with_valid_from(date);
+    }
+    if let Some(date) = valid_until {
+        tag = tag.with_valid_until(date);
+    }
+    tag.check()
+        .map_err(|e| ParseError::invalid(&span, Rule::price, e.to_string()))?;
+    Ok(tag)
+}
+
+/// Reads an amount and its currency, reporting an unknown currency at the code and any other
+/// problem at the amount.
+fn parse_money(pair: Pair<Rule>) -> Result<Price, ParseError> {
+    let span = pair.as_span();
+    let amount = value_of(pair.clone(), Rule::number)
+        .ok_or_else(|| ParseError::missing(&span, Rule::price))?;
+    let currency =
+        value_of(pair, Rule::currency).ok_or_else(|| ParseError::missing(&span, Rule::price))?;
+    Price::parse(amount.as_str(), currency.as_str()).map_err(|e| {
+        let span = match e {
+            PriceError::UnknownCurrency(_) => currency.as_span(),
+            _ => amount.as_span(),
+        };
+        ParseError::invalid(&span, Rule::price, e.to_string())
+    })
+}
+
+/// Parses the `value` part of a field with the value type's `FromStr`, reporting its error at
+/// the value.
+fn parse_value<T>(pair: Pair<Rule>, value: Rule) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let rule = pair.as_rule();
+    parse_part(pair, value, rule)
+}
+
+/// Like [`parse_value`] for a part of a field, such as the format of a price, reporting errors
+/// against the whole `field`.
+fn parse_part<T>(pair: Pair<Rule>, value: Rule, field: Rule) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let span = pair.as_span();
+    let value = value_of(pair, value).ok_or_else(|| ParseError::missing(&span, field))?;
+    value
+        .as_str()
+        .parse()
+        .map_err(|e: T::Err| ParseError::invalid(&value.as_span(), field, e.to_string()))
+}
+
+/// Reads a field holding free text, either quoted or plain.
+fn parse_text(pair: Pair<Rule>) -> Result<String, ParseError> {
+    let rule = pair.as_rule();
+    let span = pair.as_span();
+    let value = pair
+        .into_inner()
+        .find(|inner| {
+            matches!(
+                inner.as_rule(),
+                Rule::quoted_text | Rule::text_value | Rule::series_name
+            )
+        })
+        .ok_or_else(|| ParseError::missing(&span, rule))?;
+    let text = match value.as_rule() {
+        Rule::quoted_text => quote::unquote(value.as_str())
+            .map_err(|reason| ParseError::invalid(&value.as_span(), rule, reason))?,
+        _ => value.as_str().to_string(),
+    };
+    builder::validate_text(error::field_name(rule), &text)
+        .map_err(|e| ParseError::invalid(&value.as_span(), rule, e.message))?;
+    Ok(text)
+}
+
+fn parse_series(pair: Pair<Rule>) -> Result<Series, ParseError> {
+    let index = parse_value(pair.clone(), Rule::series_index)?;
+    Ok(Series::new(parse_text(pair)?, index))
+}
+
+fn parse_description(pair: Pair<Rule>) -> Result<String, ParseError> {
+    let Some(block) = pair
+        .clone()
+        .into_inner()
+        .find(|inner| matches!(inner.as_rule(), Rule::literal_block | Rule::fenced_block))
+    else {
+        return parse_text(pair);
+    };
+    let text = match block.as_rule() {
+        Rule::literal_block => unindent(block.as_str()),
+        _ => unfence(block.as_str()),
+    };
+    builder::validate_text("description", &text)
+        .map_err(|e| ParseError::invalid(&block.as_span(), Rule::description, e.message))?;
+    Ok(text)
+}
+
+/// The lines of a literal block after its `|` line, with the common indentation removed.
+fn unindent(block: &str) -> String {
+    let lines: Vec<&str> = block.lines().skip(1).collect();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The lines between the fences of a fenced block, as written.
+fn unfence(block: &str) -> String {
+    let lines: Vec<&str> = block.lines().collect();
+    let is_fence = |line: &&str| line.trim() == "```";
+    let first = lines.iter().position(is_fence).unwrap_or(0);
+    let last = lines.iter().rposition(is_fence).unwrap_or(lines.len());
+    lines[first + 1..last].join("\n")
+}
+
+fn extra_key(pair: &Pair<Rule>) -> String {
+    value_of(pair.clone(), Rule::field_key)
+        .map(|key| key.as_str().trim_end().to_string())
+        .unwrap_or_default()
+}
+
+/// The value of an extension field, which may be empty.
+fn parse_extra_value(pair: Pair<Rule>) -> Result<String, ParseError> {
+    let Some(value) = pair
+        .into_inner()
+        .find(|inner| matches!(inner.as_rule(), Rule::quoted_text | Rule::text_value))
+    else {
+        return Ok(String::new());
+    };
+    match value.as_rule() {
+        Rule::quoted_text => quote::unquote(value.as_str())
+            .map_err(|reason| ParseError::invalid(&value.as_span(), Rule::extra_field, reason)),
+        _ => Ok(value.as_str().to_string()),
+    }
+}
+
+fn parse_pages(pair: Pair<Rule>) -> Result<u32, ParseError> {
+    let span = pair.as_span();
+    let value =
+        value_of(pair, Rule::pages_value).ok_or_else(|| ParseError::missing(&span, Rule::pages))?;
+    value
+        .as_str()
+        .parse::<u32>()
+        .ok()
+        .filter(|pages| builder::validate_pages(*pages).is_ok())
+        .ok_or_else(|| {
+            ParseError::invalid(
+                &value.as_span(),
+                Rule::pages,
+                format!("pages must be between 1 and {}", u32::MAX),
+            )
+        })
+}
+
+/// The comment on a comment line, or the one trailing a field.
+fn comment_of(pair: &Pair<Rule>) -> Option<String> {
+    value_of(pair.clone(), Rule::comment).map(|comment| comment.as_str().trim_end().to_string())
+}
+
+/// Comments after the last book of a catalog or entry, which are kept with that book.
+fn trailing_notes(pair: &Pair<Rule>) -> Vec<String> {
+    pair.clone()
+        .into_inner()
+        .filter(|inner| inner.as_rule() == Rule::comment_line)
+        .filter_map(|line| comment_of(&line))
+        .collect()
+}
+
+fn value_of(pair: Pair<Rule>, rule: Rule) -> Option<Pair<Rule>> {
+    pair.into_inner().find(|inner| inner.as_rule() == rule)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Catalog {
+    books: Vec<Book>,
+}
+
+impl Catalog {
+    /// Parses a whole catalog file, keeping the books in document order.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self::parse_with(input, &ParseOptions::strict())?.value)
+    }
+
+    pub fn parse_with(input: &str, options: &ParseOptions) -> Result<Parsed<Self>, ParseError> {
+        if input.trim().is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+
+        let catalog = Grammar::parse(Rule::catalog, input)
+            .map_err(|error| ParseError::from_pest(error, input))?
+            .next()
+            .ok_or(ParseError::EmptyInput)?;
+
+        let mut reporter = Reporter::new(options);
+        let notes = trailing_notes(&catalog);
+
+        let mut books: Vec<Book> = catalog
+            .into_inner()
+            .filter(|pair| pair.as_rule() == Rule::books)
+            .flat_map(|books| books.into_inner())
+            .filter(|pair| pair.as_rule() == Rule::book)
+            .map(|pair| Book::build(pair, &mut reporter))
+            .collect::<Result<_, _>>()?;
+        if let Some(last) = books.last_mut() {
+            last.notes.extend(notes);
+        }
+
+        Ok(Parsed {
+            value: Catalog { books },
+            warnings: reporter.warnings,
+        })
+    }
+
+    /// Parses a catalog entry by entry, resynchronising at every `Book N:` header so that one
+    /// malformed entry does not prevent the others from being read.
+    pub fn parse_recovering(input: &str, options: &ParseOptions) -> Recovered {
+        let mut recovered = Recovered {
+            catalog: Catalog { books: Vec::new() },
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        };
+        if input.trim().is_empty() {
+            recovered.errors.push(EntryError {
+                span: Location::default(),
+                error: ParseError::EmptyInput,
+            });
+            return recovered;
+        }
+
+        for (line_offset, start, end) in entry_ranges(input) {
+            let text = &input[start..end];
+            let span = Location {
+                line: line_offset + 1,
+                column: 1,
+                start,
+                end,
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            let mut reporter = Reporter::new(options);
+            let book = Grammar::parse(Rule::entry, text)
+                .map_err(|error| ParseError::from_pest(error, text))
+                .and_then(|mut pairs| {
+                    let entry = pairs.next().ok_or(ParseError::EmptyInput)?;
+                    let notes = trailing_notes(&entry);
+                    let book = value_of(entry, Rule::book).ok_or(ParseError::EmptyInput)?;
+                    let mut book = Book::build(book, &mut reporter)?;
+                    book.notes.extend(notes);
+                    Ok(book)
+                });
+
+            recovered
+                .warnings
+                .extend(reporter.warnings.into_iter().map(|warning| Warning {
+                    location: warning.location.shifted(line_offset, start),
+                    ..warning
+                }));
+            match book {
+                Ok(book) => recovered.catalog.books.push(book),
+                Err(error) => recovered.errors.push(EntryError {
+                    span,
+                    error: error.shifted(line_offset, start),
+                }),
+            }
+        }
+
+        recovered
+    }
+
+    pub fn to_json(&self, options: &JsonOptions) -> serde_json::Result<serde_json::Value> {
+        self.books
+            .iter()
+            .map(|book| book.to_json(options))
+            .collect::<Result<_, _>>()
+            .map(serde_json::Value::Array)
+    }
+
+    /// Reports duplicate book numbers, gaps between consecutive numbers and numbers that go
+    /// backwards, in document order.
+    pub fn check_numbering(&self) -> Vec<NumberingIssue> {
+        let mut issues = Vec::new();
+        let mut positions: HashMap<u64, usize> = HashMap::new();
+        let mut highest: Option<u64> = None;
+
+        for (index, book) in self.books.iter().enumerate() {
+            let id = book.id;
+            if let Some(&first) = positions.get(&id) {
+                issues.push(NumberingIssue::Duplicate {
+                    id,
+                    first,
+                    second: index,
+                });
+                continue;
+            }
+            positions.insert(id, index);
+
+            match highest {
+                Some(previous) if id < previous => {
+                    issues.push(NumberingIssue::NonMonotonic { previous, id });
+                }
+                Some(previous) if id - previous > 1 => {
+                    issues.push(NumberingIssue::Gap {
+                        after: previous,
+                        next: id,
+                    });
+                    highest = Some(id);
+                }
+                _ => highest = Some(id),
+            }
+        }
+
+        issues
+    }
+
+    /// The books with a genre that is `genre` or one of its sub-genres in `taxonomy`.
+    pub fn books_in_genre(&self, genre: &str, taxonomy: &GenreTaxonomy) -> Vec<&Book> {
+        self.books
+            .iter()
+            .filter(|book| {
+                book.genres
+                    .iter()
+                    .any(|book_genre| taxonomy.is_within(book_genre, genre))
+            })
+            .collect()
+    }
+
+    /// The books of a series ordered by volume number, keeping document order for equal numbers.
+    pub fn series(&self, name: &str) -> Vec<&Book> {
+        let mut books: Vec<&Book> = self
+            .books
+            .iter()
+            .filter(|book| {
+                book.series
+                    .as_ref()
+                    .is_some_and(|series| series.name() == name)
+            })
+            .collect();
+        books.sort_by_key(|book| book.series.as_ref().map(Series::index));
+        books
+    }
+
+    /// The names of all series in the catalog, in the order they first appear.
+    pub fn series_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for series in self.books.iter().filter_map(|book| book.series.as_ref()) {
+            if !names.contains(&series.name()) {
+                names.push(series.name());
+            }
+        }
+        names
+    }
+
+    /// Volume numbers from 1 up to the highest one in the catalog that no book of the series has.
+    pub fn missing_volumes(&self, name: &str) -> Vec<u32> {
+        let volumes: Vec<u32> = self
+            .series(name)
+            .iter()
+            .filter_map(|book| book.series.as_ref().map(Series::index))
+            .collect();
+        let highest = volumes.last().copied().unwrap_or(0);
+        (1..highest)
+            .filter(|index| !volumes.contains(index))
+            .collect()
+    }
+
+    pub fn books(&self) -> &[Book] {
+        &self.books
+    }
+
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NumberingIssue {
+    /// Two books share a number; `first` and `second` are their positions in the catalog.
+    Duplicate {
+        id: u64,
+        first: usize,
+        second: usize,
+    },
+    /// Numbers between `after` and `next` are missing.
+    Gap { after: u64, next: u64 },
+    /// A book is numbered lower than one before it.
+    NonMonotonic { previous: u64, id: u64 },
+}
+
+impl std::fmt::Display for NumberingIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberingIssue::Duplicate { id, first, second } => write!(
+                f,
+                "book number {} is used by entries {} and {}",
+                id,
+                first + 1,
+                second + 1
+            ),
+            NumberingIssue::Gap { after, next } if next - after == 2 => {
+                write!(f, "book number {} is missing", after + 1)
+            }
+            NumberingIssue::Gap { after, next } => {
+                write!(f, "book numbers {} to {} are missing", after + 1, next - 1)
+            }
+            NumberingIssue::NonMonotonic { previous, id } => {
+                write!(f, "book number {} comes after book number {}", id, previous)
+            }
+        }
+    }
+}
+
+/// Splits the input at `Book N:` headers, returning the starting line index and byte range of
+/// each entry. Text before the first header forms an entry of its own unless it is only comments.
+/// As in the grammar, comments after the first blank line that precedes a header belong to the
+/// entry of that header. Lines inside a fenced `Description:` block are never headers.
+fn entry_ranges(input: &str) -> Vec<(usize, usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut current = (0, 0);
+    let mut offset = 0;
+    let mut has_content = false;
+    let mut split: Option<(usize, usize)> = None;
+    let mut in_fence = false;
+    // Set after a bare `Description:` line, whose fence may open on the next line.
+    let mut fence_may_open = false;
+
+    for (index, line) in input.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+        let opens_fence = description_value(trimmed)
+            .map_or(fence_may_open && trimmed == "```", |value| value == "```");
+        fence_may_open = description_value(trimmed).is_some_and(str::is_empty);
+        if in_fence || opens_fence {
+            in_fence = if in_fence { trimmed != "```" } else { true };
+            has_content = true;
+            split = None;
+        } else if is_book_header(line) {
+            let (start_line, start) = split.unwrap_or((index, offset));
+            if has_content && start > current.1 {
+                ranges.push((current.0, current.1, start));
+                current = (start_line, start);
+            }
+            has_content = true;
+            split = None;
+        } else if trimmed.is_empty() {
+            split.get_or_insert((index + 1, offset + line.len()));
+        } else if !is_comment(trimmed) {
+            has_content = true;
+            split = None;
+        }
+        offset += line.len();
+    }
+    ranges.push((current.0, current.1, input.len()));
+
+    ranges
+}
+
+/// The trimmed text after `Description:` when `line` starts that field.
+fn description_value(line: &str) -> Option<&str> {
+    line.strip_prefix("Description:").map(str::trim)
+}
+
+fn is_comment(line: &str) -> bool {
+    line.starts_with('#') || line.starts_with("//")
+}
+
+fn is_book_header(line: &str) -> bool {
+    line.strip_prefix("Book ")
+        .map(|rest| rest.trim_start_matches([' ', '\t']))
+        .and_then(|rest| rest.split_once(':'))
+        .is_some_and(|(number, _)| {
+            let number = number.trim_end();
+            !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+/// Result of [`Catalog::parse_recovering`]: every book that could be parsed plus the errors of
+/// the entries that could not.
+#[derive(Debug)]
+pub struct Recovered {
+    pub catalog: Catalog,
+    pub errors: Vec<EntryError>,
+    pub warnings: Vec<Warning>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryError {
+    /// The whole entry that was skipped.
+    pub span: Location,
+    pub error: ParseError,
 }
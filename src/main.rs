@@ -1,5 +1,4 @@
 use books_description_parser::*;
-use pest::Parser;
 use std::env;
 use std::fs;
 use std::process;
@@ -7,7 +6,22 @@ use std::process;
 fn help() {
     println!("This is a CLI tool to parse and display book data.");
     println!("Usage:");
-    println!("  parse <file_path>   Parse the given book description file");
+    println!("  parse <file_path> [--lenient] [--color] [--legacy-price] [--plain-authors]");
+    println!("        [--reject-unknown-fields] [--taxonomy <genres.json>]");
+    println!("                      Parse every book in the given catalog file;");
+    println!("                      --lenient replaces invalid fields with defaults and warns,");
+    println!("                      --color highlights diagnostics with ANSI colours,");
+    println!("                      --legacy-price writes prices as \"199.00 UAH\" strings,");
+    println!("                      --plain-authors writes authors as a list of strings,");
+    println!("                      --reject-unknown-fields reports `Key: value` lines the");
+    println!("                      grammar does not know instead of keeping them,");
+    println!("                      --taxonomy normalises genres against a genre taxonomy");
+    println!("  convert-prices <file_path> --to <CUR> --rates <rates.csv|rates.json>");
+    println!("                 [--date YYYY-MM-DD] [--rounding half-up|half-even|down|up]");
+    println!("                      Show every book's price converted into another currency");
+    println!("  fmt <file_path> [--check]");
+    println!("                      Rewrite the catalog with canonical spacing and field order;");
+    println!("                      --check only reports whether the file would change");
     println!("  credits             Display credits information");
 }
 
@@ -23,6 +37,89 @@ fn parse_file(file_path: &str) -> Result<String, String> {
     Ok(cleaned_content)
 }
 
+fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    println!("Error: {}", message);
+    process::exit(1);
+}
+
+fn convert_prices(args: &[String]) {
+    let Some(file_path) = args.first() else {
+        fail("Missing file path. Usage: convert-prices <file_path> --to <CUR> --rates <file>");
+    };
+    let to: Currency = match flag_value(args, "--to") {
+        Some(code) => code.parse().unwrap_or_else(|e| fail(e)),
+        None => fail("Missing target currency. Pass --to <CUR>"),
+    };
+    let rates = match flag_value(args, "--rates") {
+        Some(path) => ExchangeRates::load(path).unwrap_or_else(|e| fail(e)),
+        None => fail("Missing exchange rates. Pass --rates <file>"),
+    };
+    let date: Option<Date> =
+        flag_value(args, "--date").map(|date| date.parse().unwrap_or_else(|e| fail(e)));
+    let rounding: Rounding = flag_value(args, "--rounding")
+        .map(|mode| mode.parse().unwrap_or_else(|e| fail(e)))
+        .unwrap_or_default();
+
+    let input = parse_file(file_path).unwrap_or_else(|e| fail(e));
+    let catalog = match Catalog::parse(&input) {
+        Ok(catalog) => catalog,
+        Err(e) => {
+            eprint!(
+                "{}",
+                Diagnostic::from_error(&e).render(file_path, &input, false)
+            );
+            process::exit(1);
+        }
+    };
+
+    for book in catalog.books() {
+        match (book.price(), book.price_in(to, &rates, date, rounding)) {
+            (Some(price), Some(Ok(converted))) => {
+                println!("{}: {} -> {}", book.book_title(), price, converted)
+            }
+            (_, Some(Err(e))) => println!("{}: {}", book.book_title(), e),
+            _ => println!("{}: no price", book.book_title()),
+        }
+    }
+}
+
+fn format_catalog(args: &[String]) {
+    let Some(file_path) = args.first() else {
+        fail("Missing file path. Usage: fmt <file_path> [--check]");
+    };
+    let check = args[1..].iter().any(|arg| arg == "--check");
+
+    let input = parse_file(file_path).unwrap_or_else(|e| fail(e));
+    let catalog = match Catalog::parse(&input) {
+        Ok(catalog) => catalog,
+        Err(e) => {
+            eprint!(
+                "{}",
+                Diagnostic::from_error(&e).render(file_path, &input, false)
+            );
+            process::exit(1);
+        }
+    };
+
+    let formatted = catalog.to_catalog_string();
+    if formatted == input {
+        return;
+    }
+    if check {
+        println!("{} is not formatted", file_path);
+        process::exit(1);
+    }
+    fs::write(file_path, formatted)
+        .unwrap_or_else(|e| fail(format!("Failed to write file: {}", e)));
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
 
@@ -38,19 +135,63 @@ fn main() {
                 process::exit(1);
             }
             let file_path = &args[2];
+            let color = args[3..].iter().any(|arg| arg == "--color");
+            let json_options = JsonOptions {
+                legacy_price: args[3..].iter().any(|arg| arg == "--legacy-price"),
+                plain_authors: args[3..].iter().any(|arg| arg == "--plain-authors"),
+            };
+            let mut options = if args[3..].iter().any(|arg| arg == "--lenient") {
+                ParseOptions::lenient()
+            } else {
+                ParseOptions::strict()
+            };
+            options.reject_unknown_fields =
+                args[3..].iter().any(|arg| arg == "--reject-unknown-fields");
+            options.taxonomy = flag_value(&args[3..], "--taxonomy")
+                .map(|path| GenreTaxonomy::load(path).unwrap_or_else(|e| fail(e)));
             match parse_file(file_path) {
                 Ok(input) => {
-                    // Parse the input using the grammar
-                    let parsed = Grammar::parse(Rule::book, &input).expect("Failed to parse");
-                    let book_pair = parsed.into_iter().next().expect("No book found");
-
-                    let book = Book::from_pair(book_pair);
+                    // Parse every book in the catalog, skipping malformed entries
+                    let recovered = Catalog::parse_recovering(&input, &options);
+                    for warning in &recovered.warnings {
+                        let diagnostic = Diagnostic::from_warning(warning);
+                        eprint!("{}", diagnostic.render(file_path, &input, color));
+                    }
+                    for entry in &recovered.errors {
+                        let diagnostic = Diagnostic::from_error(&entry.error);
+                        eprint!("{}", diagnostic.render(file_path, &input, color));
+                    }
 
-                    println!("{:#?}", book);
+                    let catalog = recovered.catalog;
+                    for issue in catalog.check_numbering() {
+                        eprintln!("warning: {}: {}", file_path, issue);
+                    }
+                    for name in catalog.series_names() {
+                        for index in catalog.missing_volumes(name) {
+                            eprintln!(
+                                "warning: {}: series {} is missing volume {}",
+                                file_path, name, index
+                            );
+                        }
+                    }
+                    for book in catalog.books() {
+                        println!("{:#?}", book);
+                    }
 
-                    let json_output = serde_json::to_string_pretty(&book)
-                        .expect("Failed to serialize book to JSON");
+                    let json_output = catalog
+                        .to_json(&json_options)
+                        .and_then(|json| serde_json::to_string_pretty(&json))
+                        .expect("Failed to serialize catalog to JSON");
                     println!("{}", json_output);
+
+                    eprintln!(
+                        "{} parsed, {} failed",
+                        catalog.len(),
+                        recovered.errors.len()
+                    );
+                    if !recovered.errors.is_empty() {
+                        process::exit(1);
+                    }
                 }
                 Err(e) => {
                     println!("{}", e);
@@ -58,6 +199,12 @@ fn main() {
                 }
             }
         }
+        "convert-prices" => {
+            convert_prices(&args[2..]);
+        }
+        "fmt" => {
+            format_catalog(&args[2..]);
+        }
         "credits" => {
             credits();
         }
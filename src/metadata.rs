@@ -0,0 +1,191 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unknown format `{0}`, expected hardcover, paperback, ebook or audiobook")]
+pub struct UnknownFormat(pub String);
+
+/// The physical or digital form a book is published in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Hardcover,
+    Paperback,
+    Ebook,
+    Audiobook,
+}
+
+impl Format {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::Hardcover => "hardcover",
+            Format::Paperback => "paperback",
+            Format::Ebook => "ebook",
+            Format::Audiobook => "audiobook",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Format {
+    type Err = UnknownFormat;
+
+    /// Ignores case and hyphens, so `Hardcover` and `e-book` are accepted.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let key: String = text
+            .chars()
+            .filter(|c| *c != '-')
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        match key.as_str() {
+            "hardcover" => Ok(Format::Hardcover),
+            "paperback" => Ok(Format::Paperback),
+            "ebook" => Ok(Format::Ebook),
+            "audiobook" => Ok(Format::Audiobook),
+            _ => Err(UnknownFormat(text.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("`{0}` is not a well-formed BCP 47 language tag")]
+pub struct InvalidLanguageTag(pub String);
+
+/// A well-formed BCP 47 language tag such as `uk`, `en-GB` or `sr-Latn-RS`, stored with the
+/// conventional casing (`zh-Hant-TW`). Whether the subtags are registered is not checked.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LanguageTag {
+    tag: String,
+}
+
+impl LanguageTag {
+    pub fn parse(text: &str) -> Result<Self, InvalidLanguageTag> {
+        let invalid = || InvalidLanguageTag(text.to_string());
+        let subtags: Vec<&str> = text.split('-').collect();
+        if subtags
+            .iter()
+            .any(|subtag| subtag.is_empty() || subtag.len() > 8)
+            || !text.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(invalid());
+        }
+
+        let mut canonical: Vec<String> = Vec::with_capacity(subtags.len());
+        let mut rest = subtags.as_slice();
+
+        // A tag made only of private-use subtags, e.g. `x-klingon`.
+        if !rest[0].eq_ignore_ascii_case("x") {
+            let language = rest[0];
+            if !is_alpha(language) || language.len() == 4 || language.len() < 2 {
+                return Err(invalid());
+            }
+            canonical.push(language.to_ascii_lowercase());
+            rest = &rest[1..];
+
+            // Up to three extended language subtags after a two- or three-letter language.
+            let mut extlangs = 0;
+            while language.len() <= 3 && extlangs < 3 {
+                match rest.first() {
+                    Some(extlang) if extlang.len() == 3 && is_alpha(extlang) => {
+                        canonical.push(extlang.to_ascii_lowercase());
+                        rest = &rest[1..];
+                        extlangs += 1;
+                    }
+                    _ => break,
+                }
+            }
+            if let Some(script) = rest.first().filter(|s| s.len() == 4 && is_alpha(s)) {
+                let mut script = script.to_ascii_lowercase();
+                script[..1].make_ascii_uppercase();
+                canonical.push(script);
+                rest = &rest[1..];
+            }
+            if let Some(region) = rest.first().filter(|region| {
+                (region.len() == 2 && is_alpha(region))
+                    || (region.len() == 3 && region.chars().all(|c| c.is_ascii_digit()))
+            }) {
+                canonical.push(region.to_ascii_uppercase());
+                rest = &rest[1..];
+            }
+            while let Some(variant) = rest.first().filter(|variant| {
+                (5..=8).contains(&variant.len())
+                    || (variant.len() == 4 && variant.starts_with(|c: char| c.is_ascii_digit()))
+            }) {
+                canonical.push(variant.to_ascii_lowercase());
+                rest = &rest[1..];
+            }
+            while let Some(singleton) = rest
+                .first()
+                .filter(|s| s.len() == 1 && !s.eq_ignore_ascii_case("x"))
+            {
+                canonical.push(singleton.to_ascii_lowercase());
+                rest = &rest[1..];
+                let count = rest.iter().take_while(|subtag| subtag.len() >= 2).count();
+                if count == 0 {
+                    return Err(invalid());
+                }
+                canonical.extend(rest[..count].iter().map(|s| s.to_ascii_lowercase()));
+                rest = &rest[count..];
+            }
+        }
+
+        if let Some(x) = rest.first() {
+            if !x.eq_ignore_ascii_case("x") || rest.len() == 1 {
+                return Err(invalid());
+            }
+            canonical.extend(rest.iter().map(|s| s.to_ascii_lowercase()));
+        }
+
+        Ok(LanguageTag {
+            tag: canonical.join("-"),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.tag
+    }
+
+    /// The primary language subtag, e.g. `uk` for `uk-UA`.
+    pub fn language(&self) -> &str {
+        self.tag.split('-').next().unwrap_or_default()
+    }
+}
+
+fn is_alpha(subtag: &str) -> bool {
+    subtag.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.tag)
+    }
+}
+
+impl FromStr for LanguageTag {
+    type Err = InvalidLanguageTag;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        LanguageTag::parse(text)
+    }
+}
+
+impl Serialize for LanguageTag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.tag)
+    }
+}
+
+impl<'de> Deserialize<'de> for LanguageTag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,112 @@
+use crate::date::Date;
+use crate::error::{ParseError, Warning};
+use crate::taxonomy::GenreTaxonomy;
+
+/// How the parser reacts to field values it cannot use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Invalid or missing fields are returned as errors.
+    #[default]
+    Strict,
+    /// Invalid or missing fields fall back to a default value and are recorded as warnings.
+    Lenient,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub mode: Mode,
+    /// Treat `Key: value` lines the grammar has no rule for as errors instead of keeping them in
+    /// `Book::extra`. In lenient mode they are dropped with a warning.
+    pub reject_unknown_fields: bool,
+    /// Replace genres and their aliases with the taxonomy's canonical names, warning about genres
+    /// it does not know.
+    pub taxonomy: Option<GenreTaxonomy>,
+    /// The date publication dates are checked against; a later one is reported as a warning.
+    /// Defaults to today.
+    pub today: Option<Date>,
+}
+
+impl ParseOptions {
+    pub fn strict() -> Self {
+        ParseOptions {
+            mode: Mode::Strict,
+            ..Self::default()
+        }
+    }
+
+    pub fn lenient() -> Self {
+        ParseOptions {
+            mode: Mode::Lenient,
+            ..Self::default()
+        }
+    }
+}
+
+/// Switches for the JSON produced by `Book::to_json` and `Catalog::to_json`.
+#[derive(Debug, Clone, Default)]
+pub struct JsonOptions {
+    /// Write prices as a single `"199.00 UAH"` string, as older versions of the crate did.
+    pub legacy_price: bool,
+    /// Write authors as a list of plain strings such as `"Jane Doe (translator)"` instead of
+    /// objects with the name split up and the role.
+    pub plain_authors: bool,
+}
+
+/// A parsed value together with the warnings collected while building it.
+#[derive(Debug, Clone)]
+pub struct Parsed<T> {
+    pub value: T,
+    pub warnings: Vec<Warning>,
+}
+
+/// Collects warnings for a single parse and decides whether a field error is fatal.
+pub(crate) struct Reporter<'a> {
+    mode: Mode,
+    pub(crate) reject_unknown_fields: bool,
+    pub(crate) taxonomy: Option<&'a GenreTaxonomy>,
+    pub(crate) today: Date,
+    pub(crate) warnings: Vec<Warning>,
+}
+
+impl<'a> Reporter<'a> {
+    pub(crate) fn new(options: &'a ParseOptions) -> Self {
+        Reporter {
+            mode: options.mode,
+            reject_unknown_fields: options.reject_unknown_fields,
+            taxonomy: options.taxonomy.as_ref(),
+            today: options.today.unwrap_or_else(Date::today),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Returns the error in strict mode, or records it and falls back to `default` in lenient mode.
+    pub(crate) fn recover<T>(&mut self, error: ParseError, default: T) -> Result<T, ParseError>
+    where
+        T: std::fmt::Debug,
+    {
+        match self.mode {
+            Mode::Strict => Err(error),
+            Mode::Lenient => {
+                self.warnings
+                    .push(Warning::defaulted(&error, format!("{:?}", default)));
+                Ok(default)
+            }
+        }
+    }
+
+    /// Records a problem that never stops parsing, whatever the mode.
+    pub(crate) fn warn(&mut self, warning: Warning) {
+        self.warnings.push(warning);
+    }
+
+    /// Returns the error in strict mode, or records it and skips the offending input in lenient mode.
+    pub(crate) fn ignore(&mut self, error: ParseError) -> Result<(), ParseError> {
+        match self.mode {
+            Mode::Strict => Err(error),
+            Mode::Lenient => {
+                self.warnings.push(Warning::ignored(&error));
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,554 @@
+use crate::date::Date;
+use crate::metadata::Format;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Active ISO 4217 currency codes with the number of digits after the decimal separator.
+/// Kept sorted by code so lookups can binary search.
+const CURRENCIES: &[(&str, u8)] = &[
+    ("AED", 2),
+    ("AFN", 2),
+    ("ALL", 2),
+    ("AMD", 2),
+    ("ANG", 2),
+    ("AOA", 2),
+    ("ARS", 2),
+    ("AUD", 2),
+    ("AWG", 2),
+    ("AZN", 2),
+    ("BAM", 2),
+    ("BBD", 2),
+    ("BDT", 2),
+    ("BGN", 2),
+    ("BHD", 3),
+    ("BIF", 0),
+    ("BMD", 2),
+    ("BND", 2),
+    ("BOB", 2),
+    ("BRL", 2),
+    ("BSD", 2),
+    ("BTN", 2),
+    ("BWP", 2),
+    ("BYN", 2),
+    ("BZD", 2),
+    ("CAD", 2),
+    ("CDF", 2),
+    ("CHF", 2),
+    ("CLF", 4),
+    ("CLP", 0),
+    ("CNY", 2),
+    ("COP", 2),
+    ("CRC", 2),
+    ("CUP", 2),
+    ("CVE", 2),
+    ("CZK", 2),
+    ("DJF", 0),
+    ("DKK", 2),
+    ("DOP", 2),
+    ("DZD", 2),
+    ("EGP", 2),
+    ("ERN", 2),
+    ("ETB", 2),
+    ("EUR", 2),
+    ("FJD", 2),
+    ("FKP", 2),
+    ("GBP", 2),
+    ("GEL", 2),
+    ("GHS", 2),
+    ("GIP", 2),
+    ("GMD", 2),
+    ("GNF", 0),
+    ("GTQ", 2),
+    ("GYD", 2),
+    ("HKD", 2),
+    ("HNL", 2),
+    ("HTG", 2),
+    ("HUF", 2),
+    ("IDR", 2),
+    ("ILS", 2),
+    ("INR", 2),
+    ("IQD", 3),
+    ("IRR", 2),
+    ("ISK", 0),
+    ("JMD", 2),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KES", 2),
+    ("KGS", 2),
+    ("KHR", 2),
+    ("KMF", 0),
+    ("KPW", 2),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("KYD", 2),
+    ("KZT", 2),
+    ("LAK", 2),
+    ("LBP", 2),
+    ("LKR", 2),
+    ("LRD", 2),
+    ("LSL", 2),
+    ("LYD", 3),
+    ("MAD", 2),
+    ("MDL", 2),
+    ("MGA", 2),
+    ("MKD", 2),
+    ("MMK", 2),
+    ("MNT", 2),
+    ("MOP", 2),
+    ("MRU", 2),
+    ("MUR", 2),
+    ("MVR", 2),
+    ("MWK", 2),
+    ("MXN", 2),
+    ("MYR", 2),
+    ("MZN", 2),
+    ("NAD", 2),
+    ("NGN", 2),
+    ("NIO", 2),
+    ("NOK", 2),
+    ("NPR", 2),
+    ("NZD", 2),
+    ("OMR", 3),
+    ("PAB", 2),
+    ("PEN", 2),
+    ("PGK", 2),
+    ("PHP", 2),
+    ("PKR", 2),
+    ("PLN", 2),
+    ("PYG", 0),
+    ("QAR", 2),
+    ("RON", 2),
+    ("RSD", 2),
+    ("RUB", 2),
+    ("RWF", 0),
+    ("SAR", 2),
+    ("SBD", 2),
+    ("SCR", 2),
+    ("SDG", 2),
+    ("SEK", 2),
+    ("SGD", 2),
+    ("SHP", 2),
+    ("SLE", 2),
+    ("SOS", 2),
+    ("SRD", 2),
+    ("SSP", 2),
+    ("STN", 2),
+    ("SVC", 2),
+    ("SYP", 2),
+    ("SZL", 2),
+    ("THB", 2),
+    ("TJS", 2),
+    ("TMT", 2),
+    ("TND", 3),
+    ("TOP", 2),
+    ("TRY", 2),
+    ("TTD", 2),
+    ("TWD", 2),
+    ("TZS", 2),
+    ("UAH", 2),
+    ("UGX", 0),
+    ("USD", 2),
+    ("UYU", 2),
+    ("UYW", 4),
+    ("UZS", 2),
+    ("VES", 2),
+    ("VND", 0),
+    ("VUV", 0),
+    ("WST", 2),
+    ("XAF", 0),
+    ("XCD", 2),
+    ("XOF", 0),
+    ("XPF", 0),
+    ("YER", 2),
+    ("ZAR", 2),
+    ("ZMW", 2),
+    ("ZWL", 2),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PriceError {
+    #[error("`{0}` is not an ISO 4217 currency code")]
+    UnknownCurrency(String),
+    #[error("`{0}` is not a valid amount")]
+    InvalidAmount(String),
+    #[error("price must not be negative")]
+    Negative,
+    #[error("{currency} allows at most {exponent} digits after the decimal point")]
+    TooPrecise {
+        currency: &'static str,
+        exponent: u8,
+    },
+    #[error("amount is too large")]
+    Overflow,
+    #[error("the price before the discount is in {was}, not {now}")]
+    CurrencyMismatch {
+        was: &'static str,
+        now: &'static str,
+    },
+    #[error("the price before the discount must be higher than the discounted price")]
+    NotDiscounted,
+    #[error("price is valid from {from}, which is after {until}")]
+    ValidityRange { from: Date, until: Date },
+}
+
+/// An ISO 4217 currency together with its minor-unit exponent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency {
+    code: &'static str,
+    exponent: u8,
+}
+
+impl Currency {
+    pub fn from_code(code: &str) -> Option<Self> {
+        CURRENCIES
+            .binary_search_by(|(known, _)| (*known).cmp(code))
+            .ok()
+            .map(|index| Currency {
+                code: CURRENCIES[index].0,
+                exponent: CURRENCIES[index].1,
+            })
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Number of digits after the decimal separator, e.g. 2 for UAH and 0 for JPY.
+    pub fn exponent(&self) -> u8 {
+        self.exponent
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = PriceError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Currency::from_code(code).ok_or_else(|| PriceError::UnknownCurrency(code.to_string()))
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code)
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// An exact, non-negative amount of money stored in the currency's minor units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Price {
+    minor_units: u64,
+    currency: Currency,
+}
+
+impl Price {
+    pub fn new(minor_units: u64, currency: Currency) -> Self {
+        Price {
+            minor_units,
+            currency,
+        }
+    }
+
+    /// Parses a decimal amount such as `199.00` in the given currency without going through floats.
+    pub fn parse(amount: &str, currency: &str) -> Result<Self, PriceError> {
+        let currency: Currency = currency.parse()?;
+        let amount = amount.trim();
+        if amount.starts_with('-') {
+            return Err(PriceError::Negative);
+        }
+
+        let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+            return Err(PriceError::InvalidAmount(amount.to_string()));
+        }
+
+        // Trailing zeros never change the value, so `19.500` is fine for a two-digit currency.
+        let fraction = fraction.trim_end_matches('0');
+        let exponent = currency.exponent as usize;
+        if fraction.len() > exponent {
+            return Err(PriceError::TooPrecise {
+                currency: currency.code,
+                exponent: currency.exponent,
+            });
+        }
+
+        let digits = format!("{}{:0<width$}", whole, fraction, width = exponent);
+        let minor_units = digits.parse::<u64>().map_err(|_| PriceError::Overflow)?;
+
+        Ok(Price::new(minor_units, currency))
+    }
+
+    pub fn minor_units(&self) -> u64 {
+        self.minor_units
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// The amount as a decimal string with exactly the currency's number of fraction digits.
+    pub fn amount(&self) -> String {
+        let exponent = self.currency.exponent as u32;
+        if exponent == 0 {
+            return self.minor_units.to_string();
+        }
+        let scale = 10u64.pow(exponent);
+        format!(
+            "{}.{:0width$}",
+            self.minor_units / scale,
+            self.minor_units % scale,
+            width = exponent as usize
+        )
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount(), self.currency)
+    }
+}
+
+impl FromStr for Price {
+    type Err = PriceError;
+
+    /// Parses the catalog form, e.g. `199.00 UAH`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (amount, currency) = text
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| PriceError::InvalidAmount(text.to_string()))?;
+        Price::parse(amount, currency.trim())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PriceFields {
+    amount: String,
+    currency: Currency,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PriceRepr {
+    Fields(PriceFields),
+    Legacy(String),
+}
+
+impl Serialize for Price {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PriceFields {
+            amount: self.amount(),
+            currency: self.currency,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Price {
+    /// Accepts both `{"amount": "199.00", "currency": "UAH"}` and the legacy `"199.00 UAH"`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match PriceRepr::deserialize(deserializer)? {
+            PriceRepr::Fields(fields) => {
+                Price::parse(&fields.amount, fields.currency.code).map_err(serde::de::Error::custom)
+            }
+            PriceRepr::Legacy(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// One `Price:` line: the amount, with the price before a discount and the format and days it
+/// applies to when the catalog gives them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceTag {
+    price: Price,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    was: Option<Price>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    format: Option<Format>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_from: Option<Date>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_until: Option<Date>,
+}
+
+impl PriceTag {
+    pub fn new(price: Price) -> Self {
+        PriceTag {
+            price,
+            was: None,
+            format: None,
+            valid_from: None,
+            valid_until: None,
+        }
+    }
+
+    pub fn with_was(self, was: Price) -> Self {
+        PriceTag {
+            was: Some(was),
+            ..self
+        }
+    }
+
+    pub fn with_format(self, format: Format) -> Self {
+        PriceTag {
+            format: Some(format),
+            ..self
+        }
+    }
+
+    pub fn with_valid_from(self, date: Date) -> Self {
+        PriceTag {
+            valid_from: Some(date),
+            ..self
+        }
+    }
+
+    pub fn with_valid_until(self, date: Date) -> Self {
+        PriceTag {
+            valid_until: Some(date),
+            ..self
+        }
+    }
+
+    /// The price to pay, after any discount.
+    pub fn price(&self) -> Price {
+        self.price
+    }
+
+    /// The price before the discount, for a `was ... now ...` line.
+    pub fn was(&self) -> Option<Price> {
+        self.was
+    }
+
+    /// The format this price is for, or `None` when it applies to every format.
+    pub fn format(&self) -> Option<Format> {
+        self.format
+    }
+
+    pub fn valid_from(&self) -> Option<Date> {
+        self.valid_from
+    }
+
+    pub fn valid_until(&self) -> Option<Date> {
+        self.valid_until
+    }
+
+    /// Whether the price applies on `date`; both ends of the range are included.
+    pub fn is_valid_on(&self, date: Date) -> bool {
+        self.valid_from.is_none_or(|from| from <= date)
+            && self.valid_until.is_none_or(|until| date <= until)
+    }
+
+    /// Whether `other` prices the same offer, i.e. the same currency, format and dates, so that
+    /// only one of them can hold.
+    pub(crate) fn same_offer(&self, other: &PriceTag) -> bool {
+        self.price.currency == other.price.currency
+            && self.format == other.format
+            && self.valid_from == other.valid_from
+            && self.valid_until == other.valid_until
+    }
+
+    pub(crate) fn check(&self) -> Result<(), PriceError> {
+        if let Some(was) = self.was {
+            if was.currency != self.price.currency {
+                return Err(PriceError::CurrencyMismatch {
+                    was: was.currency.code,
+                    now: self.price.currency.code,
+                });
+            }
+            if was.minor_units <= self.price.minor_units {
+                return Err(PriceError::NotDiscounted);
+            }
+        }
+        if let (Some(from), Some(until)) = (self.valid_from, self.valid_until) {
+            if from > until {
+                return Err(PriceError::ValidityRange { from, until });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<Price> for PriceTag {
+    fn from(price: Price) -> Self {
+        PriceTag::new(price)
+    }
+}
+
+impl fmt::Display for PriceTag {
+    /// Writes the line in catalog form, e.g. `was 249.00 UAH now 199.00 UAH for ebook`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(was) = self.was {
+            write!(f, "was {} now ", was)?;
+        }
+        write!(f, "{}", self.price)?;
+        if let Some(format) = self.format {
+            write!(f, " for {}", format)?;
+        }
+        if let Some(from) = self.valid_from {
+            write!(f, " from {}", from)?;
+        }
+        if let Some(until) = self.valid_until {
+            write!(f, " until {}", until)?;
+        }
+        Ok(())
+    }
+}
+
+/// The prices of a book. They are written to JSON as `price`, holding the first amount as it
+/// always was, plus `prices` with every line when there is more than a single plain amount.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PriceList(pub(crate) Vec<PriceTag>);
+
+impl PriceList {
+    pub(crate) fn first(&self) -> Option<&Price> {
+        self.0.first().map(|tag| &tag.price)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PriceListFields {
+    price: Option<Price>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prices: Option<Vec<PriceTag>>,
+}
+
+impl Serialize for PriceList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let plain = match self.0.as_slice() {
+            [] => true,
+            [only] => *only == PriceTag::new(only.price),
+            _ => false,
+        };
+        PriceListFields {
+            price: self.0.first().map(PriceTag::price),
+            prices: (!plain).then(|| self.0.clone()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PriceList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = PriceListFields::deserialize(deserializer)?;
+        Ok(PriceList(match fields.prices {
+            Some(prices) => prices,
+            None => fields.price.into_iter().map(PriceTag::new).collect(),
+        }))
+    }
+}
@@ -0,0 +1,75 @@
+/// Removes the delimiters of a `quoted_text` match and resolves its escape sequences.
+pub(crate) fn unquote(quoted: &str) -> Result<String, String> {
+    let mut chars = quoted.chars();
+    chars.next();
+    chars.next_back();
+
+    let mut text = String::new();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('u') => {
+                let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                let c = u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("`\\u{{{}}}` is not a Unicode scalar value", code))?;
+                text.push(c);
+            }
+            Some(c) => text.push(c),
+            None => return Err("quoted text ends with a lone backslash".to_string()),
+        }
+    }
+    Ok(text)
+}
+
+/// Writes `text` between straight double quotes, escaping whatever `unquote` would resolve.
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Writes a list item bare when it would read back unchanged, and quoted otherwise.
+pub(crate) fn quote_item(text: &str) -> String {
+    let bare = !text.is_empty()
+        && text.trim() == text
+        && !text.starts_with(['"', '“', '«'])
+        && !text.contains([',', '[', ']', '\n'])
+        && !text.chars().any(char::is_control);
+    if bare {
+        text.to_string()
+    } else {
+        quote(text)
+    }
+}
+
+/// Writes a free-text field value bare when it would read back unchanged, and quoted otherwise.
+pub(crate) fn quote_text(text: &str) -> String {
+    let bare = !text.is_empty()
+        && text.trim() == text
+        && !text.starts_with(['"', '“', '«'])
+        && ![" #", "\t#", " //", "\t//"]
+            .iter()
+            .any(|comment| text.contains(comment))
+        && !text.chars().any(char::is_control);
+    if bare {
+        text.to_string()
+    } else {
+        quote(text)
+    }
+}
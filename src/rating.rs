@@ -0,0 +1,200 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RatingError {
+    #[error("`{0}` is not a rating such as 8.5, 4.5/5 or 87%")]
+    Format(String),
+    #[error("rating must be a number between 0 and {0}")]
+    OutOfRange(String),
+    #[error("a rating scale must go up to at least 1")]
+    Scale,
+}
+
+/// The scale a rating was given on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RatingScale {
+    /// A bare number from 0 to 10, e.g. `8.5`.
+    #[default]
+    Ten,
+    /// A number out of a maximum, e.g. `4.5/5`.
+    OutOf(u32),
+    /// A percentage, e.g. `87%`.
+    Percent,
+}
+
+impl RatingScale {
+    /// The highest value a rating on this scale can have.
+    pub fn max(&self) -> f32 {
+        match self {
+            RatingScale::Ten => 10.0,
+            RatingScale::OutOf(max) => *max as f32,
+            RatingScale::Percent => 100.0,
+        }
+    }
+}
+
+/// A rating as written in the catalog together with its scale, so that `4.5/5` is written back
+/// as `4.5/5` while still comparing with other ratings through [`Rating::normalized`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    value: f32,
+    scale: RatingScale,
+}
+
+impl Rating {
+    pub fn new(value: f32, scale: RatingScale) -> Result<Self, RatingError> {
+        let rating = Rating { value, scale };
+        rating.check()?;
+        Ok(rating)
+    }
+
+    /// A rating on the default 0–10 scale.
+    pub fn out_of_ten(value: f32) -> Result<Self, RatingError> {
+        Self::new(value, RatingScale::Ten)
+    }
+
+    /// Reads `8.5`, `4.5/5` or `87%`.
+    pub fn parse(text: &str) -> Result<Self, RatingError> {
+        let text = text.trim();
+        let invalid = || RatingError::Format(text.to_string());
+        let (number, scale) = if let Some(number) = text.strip_suffix('%') {
+            (number, RatingScale::Percent)
+        } else if let Some((number, max)) = text.split_once('/') {
+            if !max.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            (
+                number,
+                RatingScale::OutOf(max.parse().map_err(|_| invalid())?),
+            )
+        } else {
+            (text, RatingScale::Ten)
+        };
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            return Err(invalid());
+        }
+        Self::new(number.parse().map_err(|_| invalid())?, scale)
+    }
+
+    /// Builds a rating without checking its range; `check` is run when the book is built.
+    pub(crate) fn unchecked(value: f32, scale: RatingScale) -> Self {
+        Rating { value, scale }
+    }
+
+    pub(crate) fn check(&self) -> Result<(), RatingError> {
+        if self.scale == RatingScale::OutOf(0) {
+            return Err(RatingError::Scale);
+        }
+        if !(0.0..=self.scale.max()).contains(&self.value) {
+            return Err(RatingError::OutOfRange(self.scale.max().to_string()));
+        }
+        Ok(())
+    }
+
+    /// The number as written, on the rating's own scale.
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn scale(&self) -> RatingScale {
+        self.scale
+    }
+
+    /// The rating moved onto the 0–10 scale, e.g. 9 for `4.5/5` and 8.7 for `87%`.
+    pub fn normalized(&self) -> f32 {
+        self.value / self.scale.max() * 10.0
+    }
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `-0` would not parse back, and `0` means the same thing.
+        let value = self.value.abs();
+        match self.scale {
+            RatingScale::Ten => write!(f, "{}", value),
+            RatingScale::OutOf(max) => write!(f, "{}/{}", value, max),
+            RatingScale::Percent => write!(f, "{}%", value),
+        }
+    }
+}
+
+impl FromStr for Rating {
+    type Err = RatingError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Rating::parse(text)
+    }
+}
+
+impl Serialize for Rating {
+    /// A rating on the 0–10 scale is written as a number, as `rating` always was; one on another
+    /// scale is written as text such as `"4.5/5"`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.scale {
+            RatingScale::Ten => serializer.serialize_f32(self.value),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+/// A rating from one source, such as a review site, with the number of votes behind it when known.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceRating {
+    source: String,
+    rating: Rating,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    votes: Option<u64>,
+}
+
+impl SourceRating {
+    pub fn new(source: impl Into<String>, rating: Rating, votes: Option<u64>) -> Self {
+        SourceRating {
+            source: source.into(),
+            rating,
+            votes,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn rating(&self) -> Rating {
+        self.rating
+    }
+
+    pub fn votes(&self) -> Option<u64> {
+        self.votes
+    }
+}
+
+impl fmt::Display for SourceRating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.source, self.rating)?;
+        match self.votes {
+            Some(1) => write!(f, " (1 vote)"),
+            Some(votes) => write!(f, " ({} votes)", votes),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RatingRepr {
+    Number(f32),
+    Text(String),
+}
+
+impl<'de> Deserialize<'de> for Rating {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match RatingRepr::deserialize(deserializer)? {
+            RatingRepr::Number(value) => Rating::out_of_ten(value),
+            RatingRepr::Text(text) => text.parse(),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The series a book belongs to and its place in it, written as `Series: "The Expanse" #3`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Series {
+    name: String,
+    index: u32,
+}
+
+impl Series {
+    pub fn new(name: impl Into<String>, index: u32) -> Self {
+        Series {
+            name: name.into(),
+            index,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The volume number within the series.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+impl fmt::Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} #{}", self.name, self.index)
+    }
+}
@@ -0,0 +1,149 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TaxonomyError {
+    #[error("failed to read genre taxonomy: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to read JSON genre taxonomy: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("genre or alias `{0}` is defined more than once")]
+    Duplicate(String),
+    #[error("parent `{parent}` of genre `{genre}` is not defined")]
+    UnknownParent { genre: String, parent: String },
+    #[error("genre `{0}` is its own ancestor")]
+    Cycle(String),
+}
+
+#[derive(Deserialize)]
+struct JsonGenre {
+    name: String,
+    #[serde(default)]
+    parent: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+/// A tree of known genres with their aliases, loaded from a local file. Names are matched
+/// ignoring case and treating hyphens, underscores and runs of spaces alike, so "Sci-Fi" and
+/// "sci  fi" are the same name.
+#[derive(Debug, Clone, Default)]
+pub struct GenreTaxonomy {
+    names: Vec<String>,
+    parents: Vec<Option<usize>>,
+    lookup: HashMap<String, usize>,
+}
+
+impl GenreTaxonomy {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TaxonomyError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Reads a list of `{"name": "Thriller", "parent": "Fiction", "aliases": ["Suspense"]}`.
+    /// A parent may be given by its name or one of its aliases and may be listed after its
+    /// sub-genres.
+    pub fn from_json(text: &str) -> Result<Self, TaxonomyError> {
+        let entries: Vec<JsonGenre> = serde_json::from_str(text)?;
+        let mut taxonomy = GenreTaxonomy::default();
+
+        for (index, entry) in entries.iter().enumerate() {
+            for name in std::iter::once(&entry.name).chain(&entry.aliases) {
+                if taxonomy.lookup.insert(genre_key(name), index).is_some() {
+                    return Err(TaxonomyError::Duplicate(name.clone()));
+                }
+            }
+            taxonomy.names.push(entry.name.clone());
+        }
+
+        for entry in &entries {
+            let parent =
+                match &entry.parent {
+                    Some(parent) => Some(taxonomy.index_of(parent).ok_or_else(|| {
+                        TaxonomyError::UnknownParent {
+                            genre: entry.name.clone(),
+                            parent: parent.clone(),
+                        }
+                    })?),
+                    None => None,
+                };
+            taxonomy.parents.push(parent);
+        }
+
+        for (index, name) in taxonomy.names.iter().enumerate() {
+            if taxonomy.ancestor_indices(index).count() >= taxonomy.names.len() {
+                return Err(TaxonomyError::Cycle(name.clone()));
+            }
+        }
+
+        Ok(taxonomy)
+    }
+
+    /// The canonical name of a genre given by its name or one of its aliases.
+    pub fn canonical(&self, genre: &str) -> Option<&str> {
+        self.index_of(genre).map(|index| self.names[index].as_str())
+    }
+
+    pub fn contains(&self, genre: &str) -> bool {
+        self.index_of(genre).is_some()
+    }
+
+    /// The genre directly above this one, e.g. "Fiction" for "Thriller".
+    pub fn parent(&self, genre: &str) -> Option<&str> {
+        let parent = self.parents[self.index_of(genre)?]?;
+        Some(&self.names[parent])
+    }
+
+    /// The genres above this one, nearest first.
+    pub fn ancestors(&self, genre: &str) -> Vec<&str> {
+        match self.index_of(genre) {
+            Some(index) => self
+                .ancestor_indices(index)
+                .map(|ancestor| self.names[ancestor].as_str())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Whether `genre` is `ancestor` or one of its sub-genres. Genres the taxonomy does not know
+    /// only match themselves.
+    pub fn is_within(&self, genre: &str, ancestor: &str) -> bool {
+        match (self.index_of(genre), self.index_of(ancestor)) {
+            (Some(genre), Some(ancestor)) => {
+                genre == ancestor || self.ancestor_indices(genre).any(|index| index == ancestor)
+            }
+            _ => genre_key(genre) == genre_key(ancestor),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    fn index_of(&self, genre: &str) -> Option<usize> {
+        self.lookup.get(&genre_key(genre)).copied()
+    }
+
+    /// Walks up the tree from `index`, stopping after as many steps as there are genres so that
+    /// a cycle cannot loop forever.
+    fn ancestor_indices(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.parents[index], |parent| self.parents[*parent])
+            .take(self.names.len())
+    }
+}
+
+/// The form names are compared in: lowercase, with hyphens and underscores read as spaces and
+/// runs of whitespace collapsed.
+fn genre_key(name: &str) -> String {
+    name.to_lowercase()
+        .replace(['-', '_'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
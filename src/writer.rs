@@ -0,0 +1,169 @@
+use crate::quote::{quote, quote_item, quote_text};
+use crate::{Book, Catalog, Contributor, Role, Series, SourceRating};
+use std::fmt;
+
+impl Book {
+    /// Writes the book in the catalog syntax accepted by `grammar.pest`, with its notes as
+    /// comment lines above the header and fields in canonical order. Parsing the result gives
+    /// back an equal `Book`.
+    pub fn to_catalog_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Catalog {
+    /// Writes every book in the catalog syntax, separated by blank lines.
+    pub fn to_catalog_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Book {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for note in self.notes() {
+            writeln!(f, "{}", note)?;
+        }
+        writeln!(f, "Book {}: {}", self.id(), quote(self.book_title()))?;
+        let authors: Vec<String> = self.authors().iter().map(contributor).collect();
+        writeln!(f, "Authors: [{}]", authors.join(", "))?;
+        writeln!(f, "Genres: [{}]", list(self.genres()))?;
+        let date = self.publication_date();
+        match date.month() {
+            Some(_) => writeln!(f, "Publication Date: {}", date)?,
+            None => writeln!(f, "Publication Year: {}", date)?,
+        }
+        if let Some(publisher) = self.publisher() {
+            writeln!(f, "Publisher: {}", quote_text(publisher))?;
+        }
+        if let Some(edition) = self.edition() {
+            writeln!(f, "Edition: {}", quote_text(edition))?;
+        }
+        if let Some(series) = self.series() {
+            writeln!(f, "Series: {}", series_value(series))?;
+        }
+        if let Some(format) = self.format() {
+            writeln!(f, "Format: {}", format)?;
+        }
+        if let Some(pages) = self.pages() {
+            writeln!(f, "Pages: {}", pages)?;
+        }
+        if let Some(language) = self.language() {
+            writeln!(f, "Language: {}", language)?;
+        }
+        if let Some(isbn) = self.isbn() {
+            writeln!(f, "ISBN: {}", isbn)?;
+        }
+        if let Some(rating) = self.scaled_rating() {
+            writeln!(f, "Rating: {}", rating)?;
+        }
+        if !self.ratings().is_empty() {
+            let ratings: Vec<String> = self.ratings().iter().map(source_rating).collect();
+            writeln!(f, "Ratings: [{}]", ratings.join(", "))?;
+        }
+        for price in self.prices() {
+            writeln!(f, "Price: {}", price)?;
+        }
+        if let Some(description) = self.description() {
+            write_description(f, description)?;
+        }
+        for (key, value) in self.extra().iter() {
+            if value.is_empty() {
+                writeln!(f, "{}:", key)?;
+            } else {
+                writeln!(f, "{}: {}", key, quote_text(value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Catalog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, book) in self.books().iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", book)?;
+        }
+        Ok(())
+    }
+}
+
+fn list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| quote_item(item))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn contributor(contributor: &Contributor) -> String {
+    let name = contributor.name();
+    // A bare name ending in something like "(editor)" would read back as a role.
+    let name = if Contributor::parse(name).name() != name {
+        quote(name)
+    } else {
+        quote_item(name)
+    };
+    match contributor.role() {
+        Role::Author => name,
+        role => format!("{} ({})", name, role),
+    }
+}
+
+fn source_rating(rating: &SourceRating) -> String {
+    let source = rating.source();
+    // A bare source ends at the first space followed by a digit, so "Top 100" has to be quoted.
+    let needs_quotes = quote_item(source) != source
+        || source.contains('(')
+        || source
+            .split_whitespace()
+            .skip(1)
+            .any(|word| word.starts_with(|c: char| c.is_ascii_digit()));
+    if needs_quotes {
+        SourceRating::new(quote(source), rating.rating(), rating.votes()).to_string()
+    } else {
+        rating.to_string()
+    }
+}
+
+/// Writes the series name bare when it would read back unchanged and quoted otherwise, followed by
+/// the volume number.
+fn series_value(series: &Series) -> String {
+    let name = match quote_text(series.name()) {
+        bare if !bare.contains('#') => bare,
+        _ => quote(series.name()),
+    };
+    format!("{} #{}", name, series.index())
+}
+
+/// Writes a single-line description inline, a multi-line one as a `|` block indented by two
+/// spaces, and falls back to an escaped quoted string when a block would not read back the same.
+fn write_description(f: &mut fmt::Formatter<'_>, description: &str) -> fmt::Result {
+    if !description.contains('\n') {
+        return writeln!(f, "Description: {}", quote_text(description));
+    }
+
+    let lines: Vec<&str> = description.split('\n').collect();
+    let is_blank = |line: &&str| line.trim().is_empty();
+    let fits_block = lines.first().is_some_and(|line| !is_blank(line))
+        && lines.last().is_some_and(|line| !is_blank(line))
+        && lines.iter().all(|line| !is_blank(line) || line.is_empty())
+        && lines.iter().any(|line| !line.starts_with([' ', '\t']))
+        && !description
+            .chars()
+            .any(|c| c.is_control() && c != '\n' && c != '\t');
+    if !fits_block {
+        return writeln!(f, "Description: {}", quote(description));
+    }
+
+    writeln!(f, "Description: |")?;
+    for line in lines {
+        if line.is_empty() {
+            writeln!(f)?;
+        } else {
+            writeln!(f, "  {}", line)?;
+        }
+    }
+    Ok(())
+}
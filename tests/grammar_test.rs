@@ -148,9 +148,15 @@ mod tests {
         assert_eq!(pair.as_span().start(), 0);
         assert_eq!(pair.as_span().end(), 12);
 
-        // Тест некоректного рейтингу (значення вище 10)
-        let pair = Grammar::parse(Rule::rating, "Rating: 10.5\n");
-        assert!(pair.is_err(), "Expected error but got {:?}", pair);
+        // Тест некоректного рейтингу (значення вище 10 відхиляється під час побудови книги)
+        let input =
+            "Book 1: \"A\"\nAuthors: []\nGenres: []\nPublication Year: 2000\nRating: 10.5\n";
+        let result = input.parse::<Book>();
+        assert!(
+            matches!(result, Err(ParseError::InvalidValue { ref field, .. }) if field == "rating"),
+            "Expected error but got {:?}",
+            result
+        );
 
         // Тест некоректного формату (без значення)
         let pair = Grammar::parse(Rule::rating, "Rating:\n");
@@ -355,6 +361,15 @@ mod tests {
         assert_eq!(pair.as_span().start(), 0);
         assert_eq!(pair.as_span().end(), 3);
 
+        // Тест на максимальне значення рейтингу
+        let pair = Grammar::parse(Rule::rating_value, "10")?
+            .next()
+            .ok_or_else(|| anyhow!("no pair"))?;
+
+        assert_eq!(pair.as_str(), "10");
+        assert_eq!(pair.as_span().start(), 0);
+        assert_eq!(pair.as_span().end(), 2);
+
         // Тест на некоректність рядка (буква)
         let pair = Grammar::parse(Rule::rating_value, "a");
         assert!(pair.is_err(), "Expected error but got {:?}", pair);
@@ -566,7 +581,7 @@ Price: 220.00 UAH
 
         // Тест на неповний вхід
         let pair = Grammar::parse(
-            Rule::books,
+            Rule::catalog,
             r#"Book 1: "First Book"
 Authors: [Author1, Author2]
 Genres: [Fiction]
@@ -582,4 +597,1423 @@ Price: 120.00 UAH"#,
 
         Ok(())
     }
+
+    #[test]
+    fn test_catalog() -> anyhow::Result<()> {
+        let input = r#"
+Book 1: "First Book"
+Authors: [Author1, Author2]
+Genres: [Fiction]
+Publication Year: 2020
+Rating: 8.0
+Price: 120.00 UAH
+
+Book 2: "Second Book"
+Authors: [Author3]
+Genres: [Non-Fiction, Biography]
+Publication Year: 2021
+Rating: 9.0
+Price: 200.00 UAH
+
+"#;
+
+        let pair = Grammar::parse(Rule::catalog, input)?
+            .next()
+            .ok_or_else(|| anyhow!("no pair"))?;
+
+        assert_eq!(pair.as_str(), input);
+
+        // Тест на те, що всі книги зберігаються в порядку документа
+        let catalog = Catalog::parse(input)?;
+        assert_eq!(catalog.len(), 2);
+
+        let json = serde_json::to_value(&catalog)?;
+        assert_eq!(json[0]["book_title"], "First Book");
+        assert_eq!(json[1]["book_title"], "Second Book");
+
+        // Тест на зайвий текст після останньої книги
+        let result = Catalog::parse(&format!("{input}garbage"));
+        assert!(result.is_err(), "Expected error but got {:?}", result);
+
+        // Тест на порожній вхід
+        let result = Catalog::parse("");
+        assert!(result.is_err(), "Expected error but got {:?}", result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() -> anyhow::Result<()> {
+        // Тест на порожній вхід
+        assert_eq!(Catalog::parse("\n\n").err(), Some(ParseError::EmptyInput));
+
+        // Тест на синтаксичну помилку з номером рядка та назвою поля
+        let input = "Book 1: \"A\"\nAuthors: [X]\nGenres: Y\nPublication Year: 1999\n";
+        match Catalog::parse(input) {
+            Err(ParseError::Syntax {
+                location, field, ..
+            }) => {
+                assert_eq!((location.line, location.column), (3, 9));
+                assert_eq!(field.as_deref(), Some("genres"));
+            }
+            other => panic!("Expected syntax error but got {:?}", other),
+        }
+
+        // Тест на відсутнє обов'язкове поле
+        let input = "Book 1: \"A\"\nAuthors: [X]\nPublication Year: 1999\n";
+        match Catalog::parse(input) {
+            Err(ParseError::MissingField { location, field }) => {
+                assert_eq!(location.line, 1);
+                assert_eq!(field, "genres");
+            }
+            other => panic!("Expected missing field but got {:?}", other),
+        }
+
+        // Тест на рік, що не вміщується в u16
+        let input = "Book 1: \"A\"\nAuthors: [X]\nGenres: [Y]\nPublication Year: 99999\nRating: 9\nPrice: 1 UAH\n";
+        match Catalog::parse(input) {
+            Err(ParseError::InvalidValue {
+                location,
+                field,
+                value,
+                ..
+            }) => {
+                assert_eq!((location.line, location.column), (4, 19));
+                assert_eq!(field, "publication_year");
+                assert_eq!(value, "99999");
+            }
+            other => panic!("Expected invalid value but got {:?}", other),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_modes() -> anyhow::Result<()> {
+        let input = "Book 1: \"\"\nAuthors: [X]\nGenres: [Y]\nPublication Year: 99999\nRating: 9\nPrice: 1 UAH\n";
+
+        // Тест на суворий режим: перше некоректне поле повертається як помилка
+        let result = Catalog::parse_with(input, &ParseOptions::strict());
+        match result {
+            Err(ParseError::InvalidValue { field, .. }) => assert_eq!(field, "book_title"),
+            other => panic!("Expected invalid value but got {:?}", other),
+        }
+
+        // Тест на м'який режим: значення за замовчуванням і попередження для кожного поля
+        let parsed = Catalog::parse_with(input, &ParseOptions::lenient())?;
+        assert_eq!(parsed.value.len(), 1);
+
+        let fields: Vec<&str> = parsed.warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, vec!["book_title", "publication_year"]);
+        assert_eq!(parsed.warnings[1].location.line, 4);
+
+        let json = serde_json::to_value(&parsed.value)?;
+        assert_eq!(json[0]["publication_year"], 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diagnostic_render() -> anyhow::Result<()> {
+        let input = "Book 1: \"A\"\nAuthors: [X]\nGenres: [Y]\nPublication Year: 1999\nRating: 9\nPrice: 1\n";
+        let error = Catalog::parse(input)
+            .err()
+            .ok_or_else(|| anyhow!("expected error"))?;
+
+        // Тест на рядок джерела, каретку та підказку
+        let rendered = Diagnostic::from_error(&error).render("books.txt", input, false);
+        assert_eq!(
+            rendered,
+            "error: syntax error in `price`, expected currency\n \
+             --> books.txt:6:9\n  \
+             |\n\
+             6 | Price: 1\n  \
+             |         ^\n  \
+             = hint: currency code expected after price, e.g. `UAH`\n"
+        );
+
+        // Тест на ANSI кольори
+        let rendered = Diagnostic::from_error(&error).render("books.txt", input, true);
+        assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_recovering() -> anyhow::Result<()> {
+        let input = r#"Book 1: "First Book"
+Authors: [Author1, Author2]
+Genres: [Fiction]
+Publication Year: 2020
+Rating: 8.0
+Price: 120.00 UAH
+
+Book 2: "Second Book"
+Authors: [Author3]
+Genres: [Non-Fiction, Biography]
+Publication Year: 2021
+Rating: 9.0
+Price: 200.00
+
+Book 3: "Third Book"
+Authors: [Author3]
+Genres: [Non-Fiction, Biography]
+Publication Year: 2020
+Rating: 8.5
+Price: 220.00 UAH
+"#;
+
+        // Тест на те, що некоректний запис не зупиняє розбір решти каталогу
+        let recovered = Catalog::parse_recovering(input, &ParseOptions::strict());
+        assert_eq!(recovered.catalog.len(), 2);
+        assert_eq!(recovered.errors.len(), 1);
+
+        let json = serde_json::to_value(&recovered.catalog)?;
+        assert_eq!(json[0]["book_title"], "First Book");
+        assert_eq!(json[1]["book_title"], "Third Book");
+
+        // Тест на позицію помилки відносно всього файлу
+        let entry = &recovered.errors[0];
+        assert_eq!(entry.span.line, 8);
+        assert_eq!(entry.error.location().map(|l| l.line), Some(13));
+        assert_eq!(entry.error.field(), Some("price"));
+
+        // Тест на рядок `Book N:` усередині огородженого опису
+        let fenced = "Book 1: \"Teaser\"\nAuthors: []\nGenres: []\nPublication Year: 2020\nDescription: ```\nBook 2: teaser\n```\n\nBook 3: \"Next\"\nAuthors: []\nGenres: []\nPublication Year: 2021\nDescription:\n```\nBook 4: teaser\n\n# not a comment\n```\n";
+        assert_eq!(Catalog::parse(fenced)?.len(), 2);
+        let recovered = Catalog::parse_recovering(fenced, &ParseOptions::strict());
+        assert!(recovered.errors.is_empty(), "{:?}", recovered.errors);
+        assert_eq!(recovered.catalog, Catalog::parse(fenced)?);
+
+        // Тест на порожній вхід
+        let recovered = Catalog::parse_recovering("", &ParseOptions::strict());
+        assert_eq!(recovered.errors[0].error, ParseError::EmptyInput);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_book_fields() -> anyhow::Result<()> {
+        // Тест на довільний порядок полів і відсутні необов'язкові поля
+        let input = r#"Book 1: "Unreleased"
+Publication Year: 2027
+Genres: [Fiction]
+Authors: [Jane Doe]
+"#;
+        let catalog = Catalog::parse(input)?;
+        let json = serde_json::to_value(&catalog)?;
+        assert_eq!(json[0]["authors"][0]["name"], "Jane Doe");
+        assert_eq!(json[0]["publication_year"], 2027);
+        assert!(json[0]["rating"].is_null());
+        assert!(json[0]["price"].is_null());
+
+        // Тест на повторне поле
+        let input = r#"Book 1: "Twice Priced"
+Authors: [Jane Doe]
+Genres: [Fiction]
+Publication Year: 2020
+Price: 100 UAH
+Price: 120 UAH
+"#;
+        match Catalog::parse(input) {
+            Err(ParseError::DuplicateField {
+                location,
+                field,
+                first,
+            }) => {
+                assert_eq!(field, "price");
+                assert_eq!(location.line, 6);
+                assert_eq!(first.line, 5);
+            }
+            other => panic!("Expected duplicate field but got {:?}", other),
+        }
+
+        // Тест на м'який режим: повторне поле ігнорується з попередженням
+        let parsed = Catalog::parse_with(input, &ParseOptions::lenient())?;
+        assert_eq!(parsed.warnings.len(), 1);
+        let json = serde_json::to_value(&parsed.value)?;
+        assert_eq!(json[0]["price"]["amount"], "100.00");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_price_value() -> anyhow::Result<()> {
+        // Тест на точне збереження суми в копійках
+        let price = Price::parse("199.00", "UAH")?;
+        assert_eq!(price.minor_units(), 19900);
+        assert_eq!(price.currency().exponent(), 2);
+        assert_eq!(price.to_string(), "199.00 UAH");
+
+        // Тест на валюти з іншою кількістю знаків після коми
+        assert_eq!(Price::parse("1500", "JPY")?.to_string(), "1500 JPY");
+        assert_eq!(Price::parse("1.5", "KWD")?.minor_units(), 1500);
+        assert_eq!(
+            Price::parse("1.5", "JPY"),
+            Err(PriceError::TooPrecise {
+                currency: "JPY",
+                exponent: 0
+            })
+        );
+
+        // Тест на від'ємну ціну та невідому валюту
+        assert_eq!(Price::parse("-1", "UAH"), Err(PriceError::Negative));
+        assert_eq!(
+            Price::parse("1", "ABC"),
+            Err(PriceError::UnknownCurrency("ABC".to_string()))
+        );
+
+        // Тест на помилку розбору каталогу з від'ємною ціною
+        let input =
+            "Book 1: \"A\"\nAuthors: [X]\nGenres: [Y]\nPublication Year: 2020\nPrice: -5 UAH\n";
+        match Catalog::parse(input) {
+            Err(ParseError::InvalidValue { field, value, .. }) => {
+                assert_eq!(field, "price");
+                assert_eq!(value, "-5");
+            }
+            other => panic!("Expected invalid value but got {:?}", other),
+        }
+
+        // Тест на структурований JSON і сумісний рядковий формат
+        let input =
+            "Book 1: \"A\"\nAuthors: [X]\nGenres: [Y]\nPublication Year: 2020\nPrice: 19 UAH\n";
+        let catalog = Catalog::parse(input)?;
+        let json = catalog.to_json(&JsonOptions::default())?;
+        assert_eq!(json[0]["price"]["amount"], "19.00");
+        assert_eq!(json[0]["price"]["currency"], "UAH");
+
+        let legacy = JsonOptions {
+            legacy_price: true,
+            ..JsonOptions::default()
+        };
+        assert_eq!(catalog.to_json(&legacy)?[0]["price"], "19.00 UAH");
+
+        // Тест на десеріалізацію обох форм
+        let price: Price = serde_json::from_str("\"19.00 UAH\"")?;
+        assert_eq!(price, Price::parse("19", "UAH")?);
+        let price: Price = serde_json::from_str(r#"{"amount": "19.00", "currency": "UAH"}"#)?;
+        assert_eq!(price.minor_units(), 1900);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_prices() -> anyhow::Result<()> {
+        let rates = ExchangeRates::from_csv(
+            "date,from,to,rate\n2026-01-01,EUR,UAH,45.10\n2026-06-01,EUR,UAH,48.00\n,USD,UAH,41.25\n",
+        )?;
+        let uah = Price::parse("120.00", "UAH")?;
+        let eur = Currency::from_code("EUR").ok_or_else(|| anyhow!("no EUR"))?;
+
+        // Тест на вибір курсу за датою та обернений курс
+        let date: Date = "2026-03-01".parse()?;
+        let converted = rates.convert(uah, eur, Some(date), Rounding::Down)?;
+        assert_eq!(converted.to_string(), "2.66 EUR");
+        let converted = rates.convert(uah, eur, None, Rounding::HalfUp)?;
+        assert_eq!(converted.to_string(), "2.50 EUR");
+
+        // Тест на прямий курс
+        let usd = Price::parse("10", "USD")?;
+        let hryvnia = Currency::from_code("UAH").ok_or_else(|| anyhow!("no UAH"))?;
+        let converted = rates.convert(usd, hryvnia, None, Rounding::HalfUp)?;
+        assert_eq!(converted.to_string(), "412.50 UAH");
+
+        // Тест на правила округлення
+        let rates = ExchangeRates::from_json(r#"[{"from": "UAH", "to": "EUR", "rate": 0.0025}]"#)?;
+        let price = Price::parse("1.00", "UAH")?;
+        assert_eq!(
+            rates
+                .convert(price, eur, None, Rounding::HalfUp)?
+                .minor_units(),
+            0
+        );
+        assert_eq!(
+            rates.convert(price, eur, None, Rounding::Up)?.minor_units(),
+            1
+        );
+        let price = Price::parse("6.00", "UAH")?;
+        assert_eq!(
+            rates
+                .convert(price, eur, None, Rounding::HalfUp)?
+                .minor_units(),
+            2
+        );
+        assert_eq!(
+            rates
+                .convert(price, eur, None, Rounding::HalfEven)?
+                .minor_units(),
+            2
+        );
+        let price = Price::parse("2.00", "UAH")?;
+        assert_eq!(
+            rates
+                .convert(price, eur, None, Rounding::HalfUp)?
+                .minor_units(),
+            1
+        );
+        assert_eq!(
+            rates
+                .convert(price, eur, None, Rounding::HalfEven)?
+                .minor_units(),
+            0
+        );
+
+        // Тест на відсутній курс і некоректний рядок
+        let jpy = Currency::from_code("JPY").ok_or_else(|| anyhow!("no JPY"))?;
+        assert!(rates.convert(price, jpy, None, Rounding::HalfUp).is_err());
+        assert!(ExchangeRates::from_csv("2026-02-30,EUR,UAH,45\n").is_err());
+
+        // Тест на точний курс у JSON: число, рядок і експонента дають той самий курс, що й CSV
+        let csv = ExchangeRates::from_csv(",UAH,EUR,0.024390243902439024390\n")?;
+        for rate in [
+            "0.024390243902439024390",
+            r#""0.024390243902439024390""#,
+            "2.4390243902439024390e-2",
+        ] {
+            let json = ExchangeRates::from_json(&format!(
+                r#"[{{"from": "UAH", "to": "EUR", "rate": {rate}}}]"#
+            ))?;
+            assert_eq!(json.rates(), csv.rates());
+        }
+        let tiny = ExchangeRates::from_json(r#"[{"from": "IDR", "to": "EUR", "rate": 1e-7}]"#)?;
+        let converted =
+            tiny.convert(Price::parse("1000000", "IDR")?, eur, None, Rounding::HalfUp)?;
+        assert_eq!(converted.to_string(), "0.10 EUR");
+        assert!(
+            ExchangeRates::from_json(r#"[{"from": "IDR", "to": "EUR", "rate": 1e-50}]"#).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_book_numbering() -> anyhow::Result<()> {
+        let entry = |number: u64| {
+            format!(
+                "Book {}: \"Title {}\"\nAuthors: [X]\nGenres: [Y]\nPublication Year: 2020\n",
+                number, number
+            )
+        };
+
+        // Тест на збереження номера книги
+        let catalog = Catalog::parse(&entry(42))?;
+        assert_eq!(catalog.books()[0].id(), 42);
+        assert_eq!(serde_json::to_value(&catalog)?[0]["id"], 42);
+
+        // Тест на коректну нумерацію
+        let input = [1, 2, 3].map(entry).join("\n");
+        assert!(Catalog::parse(&input)?.check_numbering().is_empty());
+
+        // Тест на дублікати, пропуски та спадну нумерацію
+        let input = [1, 2, 5, 2, 4].map(entry).join("\n");
+        let issues = Catalog::parse(&input)?.check_numbering();
+        assert_eq!(
+            issues,
+            vec![
+                NumberingIssue::Gap { after: 2, next: 5 },
+                NumberingIssue::Duplicate {
+                    id: 2,
+                    first: 1,
+                    second: 3
+                },
+                NumberingIssue::NonMonotonic { previous: 5, id: 4 },
+            ]
+        );
+        assert_eq!(issues[0].to_string(), "book numbers 3 to 4 are missing");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_book_builder() -> anyhow::Result<()> {
+        let input = r#"Book 1: "Enemy Of My Enemy"
+Authors: [Travis Casey, Melissa Mayberry]
+Genres: [Fiction, Thriller, Drama]
+Publication Year: 2016
+Rating: 9.5
+Price: 199.00 UAH
+"#;
+
+        // Тест на розбір окремого запису через FromStr та TryFrom
+        let parsed: Book = input.parse()?;
+        assert_eq!(Book::try_from(input)?, parsed);
+        assert_eq!(
+            parsed.authors(),
+            [
+                Contributor::author("Travis Casey"),
+                Contributor::author("Melissa Mayberry")
+            ]
+        );
+        assert_eq!(parsed.publication_year(), 2016);
+        assert_eq!(parsed.rating(), Some(9.5));
+
+        // Тест на побудову тієї ж книги в коді
+        let built = Book::builder()
+            .id(1)
+            .title("Enemy Of My Enemy")
+            .authors(["Travis Casey", "Melissa Mayberry"])
+            .genres(["Fiction", "Thriller", "Drama"])
+            .publication_year(2016)
+            .rating(9.5)
+            .price(Price::parse("199", "UAH")?)
+            .build()?;
+        assert_eq!(built, parsed);
+
+        // Тест на перевірку значень у будівнику
+        let error = Book::builder().title("  ").publication_year(2016).build();
+        assert_eq!(error.map_err(|e| e.field), Err("book_title"));
+        let error = Book::builder().title("A").publication_year(0).build();
+        assert_eq!(error.map_err(|e| e.field), Err("publication_year"));
+        let error = Book::builder()
+            .title("A")
+            .publication_year(2016)
+            .rating(11.0)
+            .build();
+        assert_eq!(error.map_err(|e| e.field), Err("rating"));
+
+        // Тест на некоректний запис
+        assert!("Book 1: \"A\"\n".parse::<Book>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_catalog_writer() -> anyhow::Result<()> {
+        let input = r#"Book 2: "Second"
+Price: 1500 JPY
+Genres: []
+Authors: [ Jane Doe ,John Roe]
+Rating: 10
+Publication Year: 1999
+
+Book 1: "First"
+Authors: [X]
+Genres: [Y]
+Publication Year: 2020
+"#;
+
+        // Тест на канонічний порядок полів і пробіли
+        let catalog = Catalog::parse(input)?;
+        let printed = catalog.to_catalog_string();
+        assert_eq!(
+            printed,
+            r#"Book 2: "Second"
+Authors: [Jane Doe, John Roe]
+Genres: []
+Publication Year: 1999
+Rating: 10
+Price: 1500 JPY
+
+Book 1: "First"
+Authors: [X]
+Genres: [Y]
+Publication Year: 2020
+"#
+        );
+
+        // Тест на parse(print(x)) == x
+        assert_eq!(Catalog::parse(&printed)?, catalog);
+        let book = Book::builder()
+            .id(7)
+            .title("Dr. No")
+            .author("Ian Fleming")
+            .publication_year(1958)
+            .rating(0.25)
+            .price(Price::parse("0.5", "KWD")?)
+            .build()?;
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_syntax_tree() -> anyhow::Result<()> {
+        let input = "\n\nBook 1:  \"First\"\nAuthors: [X]\nGenres:   [Y]\nPublication Year:  2020\nPrice:   199.00 UAH  \n\n\n\nBook 2: \"Second\"\nAuthors: [Z]\nGenres: []\nPublication Year: 1999\n\n";
+
+        // Тест на збереження кожного байта
+        let mut tree = SyntaxTree::parse(input)?;
+        assert_eq!(tree.to_string(), input);
+        assert_eq!(tree.entries().count(), 2);
+        let first = tree.entries().next().ok_or_else(|| anyhow!("no entry"))?;
+        assert_eq!(first.id(), Some(1));
+        assert_eq!(first.field("Price").map(|f| f.value()), Some("199.00 UAH"));
+
+        // Тест на зміну одного рядка
+        let entry = tree.entry_mut(1).ok_or_else(|| anyhow!("no entry"))?;
+        entry.set_field("Price", "249.50 UAH")?;
+        assert_eq!(
+            tree.to_string(),
+            input.replace("Price:   199.00 UAH  \n", "Price:   249.50 UAH  \n")
+        );
+
+        // Тест на додавання нового поля
+        let entry = tree.entry_mut(2).ok_or_else(|| anyhow!("no entry"))?;
+        entry.set_field("Rating", "8.5")?;
+        assert_eq!(entry.to_book()?.rating(), Some(8.5));
+        assert!(tree
+            .to_string()
+            .ends_with("Publication Year: 1999\nRating: 8.5\n\n"));
+
+        // Тест на некоректне значення
+        let entry = tree.entry_mut(2).ok_or_else(|| anyhow!("no entry"))?;
+        let before = entry.clone();
+        assert!(entry.set_field("Publication Year", "soon").is_err());
+        assert_eq!(*entry, before);
+
+        // Тест на значення, яке проходить граматику, але не є коректною книгою
+        assert!(matches!(
+            entry.set_field("Price", "1 XXX"),
+            Err(ParseError::InvalidValue { field, .. }) if field == "price"
+        ));
+        assert_eq!(*entry, before);
+
+        // Тест на видалення обов'язкового та необов'язкового поля
+        assert!(matches!(
+            entry.remove_field("Authors"),
+            Err(ParseError::MissingField { .. })
+        ));
+        assert_eq!(*entry, before);
+        assert!(entry.remove_field("Rating")?.is_some());
+        assert!(entry.remove_field("Rating")?.is_none());
+        assert_eq!(entry.to_book()?.rating(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comments() -> anyhow::Result<()> {
+        let input = "# Catalog of 2024\n\n// supplier: X\nBook 1: \"First\" # reprint pending\nAuthors: [X]\n# checked\nGenres: [Y]\nPublication Year: 2020\nPrice: 199.00 UAH // sale\n\nBook 2: \"Second\"\nAuthors: [Z]\nGenres: []\nPublication Year: 1999\n\n# end of file\n";
+
+        // Тест на окремий рядок коментаря
+        let pair = Grammar::parse(Rule::comment_line, "# reprint pending\n")?;
+        assert_eq!(pair.as_str(), "# reprint pending\n");
+
+        // Тест на коментарі як нотатки книги
+        let catalog = Catalog::parse(input)?;
+        assert_eq!(
+            catalog.books()[0].notes(),
+            [
+                "# Catalog of 2024",
+                "// supplier: X",
+                "# reprint pending",
+                "# checked",
+                "// sale"
+            ]
+        );
+        assert_eq!(catalog.books()[1].notes(), ["# end of file"]);
+        assert_eq!(
+            catalog.books()[0].price().map(Price::amount),
+            Some("199.00".into())
+        );
+
+        // Тест на збереження коментарів форматером
+        assert_eq!(Catalog::parse(&catalog.to_catalog_string())?, catalog);
+
+        // Тест на відновлення після помилки з коментарями перед записом
+        let broken = input.replace("Authors: [Z]", "Authors: Z");
+        let recovered = Catalog::parse_recovering(&broken, &ParseOptions::strict());
+        assert_eq!(recovered.catalog.books().len(), 1);
+        assert_eq!(recovered.catalog.books()[0].notes().len(), 5);
+        assert_eq!(recovered.errors.len(), 1);
+
+        // Тест на збереження коментаря при зміні поля
+        let mut tree = SyntaxTree::parse(input)?;
+        assert_eq!(tree.to_string(), input);
+        let entry = tree.entry_mut(1).ok_or_else(|| anyhow!("no entry"))?;
+        assert_eq!(entry.comments().count(), 3);
+        entry.set_field("Price", "149.00 UAH")?;
+        assert!(tree.to_string().contains("Price: 149.00 UAH // sale\n"));
+
+        // Тест на нотатку без позначки коментаря
+        assert!(Book::builder()
+            .title("X")
+            .publication_year(2000)
+            .note("reprint")
+            .build()
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quoted_escapes() -> anyhow::Result<()> {
+        // Тест на екрановані лапки всередині назви
+        let pair = Grammar::parse(Rule::quoted_text, r#""The \"Real\" Story""#)?
+            .next()
+            .ok_or_else(|| anyhow!("no pair"))?;
+        assert_eq!(pair.as_str(), r#""The \"Real\" Story""#);
+
+        // Тест на типографські лапки та лапки-ялинки
+        assert!(Grammar::parse(Rule::quoted_text, "“Кобзар”").is_ok());
+        assert!(Grammar::parse(Rule::quoted_text, "«Тіні забутих предків»").is_ok());
+        assert!(Grammar::parse(Rule::quoted_text, "“Кобзар»").is_err());
+
+        // Тест на розекранування у полі book_title
+        let fields = "Authors: [X]\nGenres: [Y]\nPublication Year: 2020\n";
+        let book: Book =
+            format!("Book 1: \"The \\\"Real\\\" Story \\\\ \\u{{2014}} end\"\n{fields}").parse()?;
+        assert_eq!(book.book_title(), "The \"Real\" Story \\ \u{2014} end");
+        let book: Book = format!("Book 1: «Сад \\» і \"дім\"»\n{fields}").parse()?;
+        assert_eq!(book.book_title(), "Сад » і \"дім\"");
+
+        // Тест на екранування у записувачі
+        let book = Book::builder()
+            .title("Line \"one\"\nLine \\two")
+            .publication_year(2000)
+            .build()?;
+        assert!(book
+            .to_catalog_string()
+            .starts_with(r#"Book 0: "Line \"one\"\nLine \\two""#));
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+
+        // Тест на некоректний код символу
+        let result = format!("Book 1: \"\\u{{d800}}\"\n{fields}").parse::<Book>();
+        assert!(matches!(result, Err(ParseError::InvalidValue { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quoted_list_items() -> anyhow::Result<()> {
+        // Тест на автора в лапках із комою
+        let pair = Grammar::parse(
+            Rule::list_of_authors,
+            "Authors: [\"Tolkien, J. R. R.\", Christopher Tolkien]\n",
+        )?
+        .next()
+        .ok_or_else(|| anyhow!("no pair"))?;
+        let authors: Vec<&str> = pair
+            .into_inner()
+            .filter(|p| p.as_rule() == Rule::author)
+            .map(|p| p.as_str())
+            .collect();
+        assert_eq!(authors, ["\"Tolkien, J. R. R.\"", "Christopher Tolkien"]);
+
+        // Тест на розекранування елементів списків
+        let input = "Book 1: \"X\"\nAuthors: [\"Tolkien, J. R. R.\", «Шевченко, Т.», Bare \"Name\" Jr]\nGenres: [\"Sci-Fi [Hard]\", \"Say \\\"hi\\\"\" , Drama]\nPublication Year: 1954\n";
+        let book: Book = input.parse()?;
+        let names: Vec<&str> = book.authors().iter().map(Contributor::name).collect();
+        assert_eq!(
+            names,
+            ["Tolkien, J. R. R.", "Шевченко, Т.", "Bare \"Name\" Jr"]
+        );
+        assert_eq!(book.genres(), ["Sci-Fi [Hard]", "Say \"hi\"", "Drama"]);
+
+        // Тест на запис у лапках лише там, де потрібно
+        let printed = book.to_catalog_string();
+        assert!(printed
+            .contains("Authors: [\"Tolkien, J. R. R.\", \"Шевченко, Т.\", Bare \"Name\" Jr]"));
+        assert!(printed.contains("Genres: [\"Sci-Fi [Hard]\", Say \"hi\", Drama]"));
+        assert_eq!(printed.parse::<Book>()?, book);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_contributors() -> anyhow::Result<()> {
+        // Тест на автора з роллю
+        let pair = Grammar::parse(Rule::list_of_authors, "Authors: [Jane Doe (translator)]\n")?
+            .next()
+            .ok_or_else(|| anyhow!("no pair"))?;
+        let author = pair
+            .into_inner()
+            .find(|p| p.as_rule() == Rule::author)
+            .ok_or_else(|| anyhow!("no author"))?;
+        let role = author
+            .into_inner()
+            .find(|p| p.as_rule() == Rule::role)
+            .ok_or_else(|| anyhow!("no role"))?;
+        assert_eq!(role.as_str(), "translator");
+
+        // Тест на ролі та невідомі дужки в імені
+        let input = "Book 1: \"X\"\nAuthors: [Travis Casey, Jane Doe (translator), \"Tolkien, J. R. R.\" (editor), John Smith (Jr)]\nGenres: []\nPublication Year: 2000\n";
+        let book: Book = input.parse()?;
+        let roles: Vec<Role> = book.authors().iter().map(Contributor::role).collect();
+        assert_eq!(
+            roles,
+            [Role::Author, Role::Translator, Role::Editor, Role::Author]
+        );
+        assert_eq!(book.authors()[3].name(), "John Smith (Jr)");
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+
+        // Тест на розбір імені та ключ сортування
+        let casey = Contributor::author("Travis Casey");
+        assert_eq!(casey.given_name(), Some("Travis"));
+        assert_eq!(casey.surname(), "Casey");
+        assert_eq!(casey.inverted_name(), "Casey, Travis");
+        assert_eq!(
+            Contributor::author("Casey, Travis").sort_key(),
+            casey.sort_key()
+        );
+        let beethoven = Contributor::author("Ludwig van Beethoven");
+        assert_eq!(beethoven.surname(), "van Beethoven");
+        assert_eq!(beethoven.inverted_name(), "Beethoven, Ludwig van");
+        let king = Contributor::author("Martin Luther King Jr.");
+        assert_eq!(king.given_name(), Some("Martin Luther"));
+        assert_eq!(king.inverted_name(), "King, Martin Luther, Jr.");
+        assert_eq!(Contributor::author("Homer").given_name(), None);
+
+        // Тест на багату та просту форму JSON
+        let json = book.to_json(&JsonOptions::default())?;
+        assert_eq!(json["authors"][1]["role"], "translator");
+        assert_eq!(json["authors"][0]["surname"], "Casey");
+        let plain = book.to_json(&JsonOptions {
+            plain_authors: true,
+            ..JsonOptions::default()
+        })?;
+        assert_eq!(plain["authors"][1], "Jane Doe (translator)");
+        assert_eq!(serde_json::from_value::<Book>(plain)?, book);
+        assert_eq!(serde_json::from_value::<Book>(json)?, book);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_fields() -> anyhow::Result<()> {
+        // Тест на правило ISBN
+        let pair = Grammar::parse(Rule::isbn, "ISBN: 978-0-306-40615-7\n")?
+            .next()
+            .ok_or_else(|| anyhow!("no pair"))?;
+        assert_eq!(pair.as_str(), "ISBN: 978-0-306-40615-7\n");
+
+        // Тест на нові поля книги
+        let input = "Book 1: \"X\"\nAuthors: [Y]\nGenres: []\nPublication Year: 2020\nISBN: 0-306-40615-2\nPublisher: Ranok  # imprint\nPages: 320\nLanguage: sr-latn-rs\nFormat: E-Book\nEdition: \"2nd, revised\"\n";
+        let book: Book = input.parse()?;
+        let isbn = book.isbn().ok_or_else(|| anyhow!("no isbn"))?;
+        assert_eq!(isbn.to_isbn13(), "9780306406157");
+        assert_eq!(isbn.to_isbn10().as_deref(), Some("0306406152"));
+        assert_eq!(book.publisher(), Some("Ranok"));
+        assert_eq!(book.pages(), Some(320));
+        assert_eq!(book.language().map(LanguageTag::as_str), Some("sr-Latn-RS"));
+        assert_eq!(book.format(), Some(Format::Ebook));
+        assert_eq!(book.edition(), Some("2nd, revised"));
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+
+        // Тест на контрольну суму та перетворення ISBN
+        assert_eq!(Isbn::parse("080442957X")?.to_isbn13(), "9780804429573");
+        assert_eq!(
+            Isbn::parse("978-0-8044-2957-3")?.to_isbn10().as_deref(),
+            Some("080442957X")
+        );
+        assert_eq!(Isbn::parse("979-10-90636-07-1")?.to_isbn10(), None);
+        assert!(Isbn::parse("978-0-306-40615-8").is_err());
+        assert!(Isbn::parse("12345").is_err());
+
+        // Тест на мовні теги BCP 47
+        assert_eq!(LanguageTag::parse("EN-gb")?.as_str(), "en-GB");
+        assert_eq!(LanguageTag::parse("zh-hant-tw")?.as_str(), "zh-Hant-TW");
+        assert!(LanguageTag::parse("de-CH-1996").is_ok());
+        assert!(LanguageTag::parse("en-a-bbb-x-priv").is_ok());
+        assert!(LanguageTag::parse("12-GB").is_err());
+        assert!(LanguageTag::parse("en--GB").is_err());
+
+        // Тест на некоректні значення
+        let base = "Book 1: \"X\"\nAuthors: [Y]\nGenres: []\nPublication Year: 2020\n";
+        for (line, field) in [
+            ("ISBN: 978-0-306-40615-8\n", "isbn"),
+            ("Language: e\n", "language"),
+            ("Format: scroll\n", "format"),
+            ("Pages: 0\n", "pages"),
+        ] {
+            let error = format!("{base}{line}")
+                .parse::<Book>()
+                .err()
+                .ok_or_else(|| anyhow!("expected error for {line}"))?;
+            assert_eq!(error.field(), Some(field));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extra_fields() -> anyhow::Result<()> {
+        // Тест на довільне поле Key: value
+        let pair = Grammar::parse(Rule::extra_field, "Supplier Code: AB-12 # new\n")?
+            .next()
+            .ok_or_else(|| anyhow!("no pair"))?;
+        assert_eq!(pair.as_str(), "Supplier Code: AB-12 # new\n");
+
+        // Тест на те, що відомі поля та заголовок книги не є довільними полями
+        assert!(Grammar::parse(Rule::extra_field, "Price: cheap\n").is_err());
+        assert!(Grammar::parse(Rule::extra_field, "Book 2: \"X\"\n").is_err());
+
+        // Тест на збереження порядку довільних полів
+        let input = "Book 1: \"X\"\nAuthors: [Y]\nШифр: 821.161.2\nGenres: []\nPublication Year: 2020\nSupplier: \"Acme, Inc.\"\nShelf:\n";
+        let book: Book = input.parse()?;
+        let extra: Vec<(&str, &str)> = book.extra().iter().collect();
+        assert_eq!(
+            extra,
+            [
+                ("Шифр", "821.161.2"),
+                ("Supplier", "Acme, Inc."),
+                ("Shelf", "")
+            ]
+        );
+        assert_eq!(book.extra().get("Supplier"), Some("Acme, Inc."));
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+
+        // Тест на JSON
+        let json = book.to_json(&JsonOptions::default())?;
+        assert_eq!(json["extra"]["Шифр"], "821.161.2");
+        let plain =
+            Catalog::parse("Book 1: \"X\"\nAuthors: []\nGenres: []\nPublication Year: 2020\n")?;
+        assert!(plain.to_json(&JsonOptions::default())?[0]
+            .get("extra")
+            .is_none());
+
+        // Тест на повторне довільне поле
+        let duplicate = format!("{input}Supplier: Other\n").parse::<Book>();
+        assert!(matches!(duplicate, Err(ParseError::DuplicateField { .. })));
+
+        // Тест на відхилення невідомих полів
+        let options = ParseOptions {
+            reject_unknown_fields: true,
+            ..ParseOptions::strict()
+        };
+        let result = Catalog::parse_with(input, &options);
+        assert!(matches!(
+            result,
+            Err(ParseError::UnknownField { ref field, .. }) if field == "Шифр"
+        ));
+        let options = ParseOptions {
+            reject_unknown_fields: true,
+            ..ParseOptions::lenient()
+        };
+        let parsed = Catalog::parse_with(input, &options)?;
+        assert!(parsed.value.books()[0].extra().is_empty());
+        assert_eq!(parsed.warnings.len(), 3);
+
+        // Тест на некоректну назву поля в побудовнику
+        let result = Book::builder()
+            .title("X")
+            .publication_year(2000)
+            .extra("Price", "1")
+            .build();
+        assert!(result.is_err());
+        for key in ["Foo ", "Foo  Bar "] {
+            let result = Book::builder()
+                .title("X")
+                .publication_year(2000)
+                .extra(key, "1")
+                .build();
+            assert!(result.is_err(), "{key:?}");
+        }
+        let book = Book::builder()
+            .title("X")
+            .publication_year(2000)
+            .extra("Foo Bar", "1")
+            .build()?;
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_description() -> anyhow::Result<()> {
+        let head = "Book 1: \"X\"\nAuthors: [Y]\nGenres: []\nPublication Year: 2020\n";
+
+        // Тест на блок із відступами та розривом абзацу
+        let input = format!("{head}Description: |\n  First line,\n    indented.\n\n  Second paragraph.\nRating: 8\n");
+        let book: Book = input.parse()?;
+        assert_eq!(
+            book.description(),
+            Some("First line,\n  indented.\n\nSecond paragraph.")
+        );
+        assert_eq!(book.rating(), Some(8.0));
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+        assert_eq!(SyntaxTree::parse(&input)?.to_string(), input);
+
+        // Тест на блок між огорожами
+        let fenced = format!("{head}Description:\n```\nOne\n\n  Two\n```\n");
+        assert_eq!(fenced.parse::<Book>()?.description(), Some("One\n\n  Two"));
+        let same_line = format!("{head}Description: ```\nOne\n```\n");
+        assert_eq!(same_line.parse::<Book>()?.description(), Some("One"));
+
+        // Тест на однорядковий опис та JSON
+        let inline = format!("{head}Description: A short book. # note\n").parse::<Book>()?;
+        assert_eq!(inline.description(), Some("A short book."));
+        let json = inline.to_json(&JsonOptions::default())?;
+        assert_eq!(json["description"], "A short book.");
+        assert!(book
+            .to_catalog_string()
+            .contains("Description: |\n  First line,"));
+
+        // Тест на опис, який не можна записати блоком
+        let book = Book::builder()
+            .title("X")
+            .publication_year(2000)
+            .description("  leading\n\ttab")
+            .build()?;
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+
+        // Тест на порожній блок
+        assert!(format!("{head}Description:\n```\n```\n")
+            .parse::<Book>()
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_series() -> anyhow::Result<()> {
+        // Тест на поле серії
+        let pair = Grammar::parse(Rule::series, "Series: \"The Expanse\" #3\n")?
+            .next()
+            .ok_or_else(|| anyhow!("series not found"))?;
+        assert_eq!(pair.as_str(), "Series: \"The Expanse\" #3\n");
+        assert!(Grammar::parse(Rule::series, "Series: Dune\n").is_err());
+
+        let input = "Book 1: \"Abaddon's Gate\"\nAuthors: [James S. A. Corey]\nGenres: []\nPublication Year: 2013\nSeries: \"The Expanse\" #3\n\n\
+                     Book 2: \"Leviathan Wakes\"\nAuthors: [James S. A. Corey]\nGenres: []\nPublication Year: 2011\nSeries: The Expanse #1 # first\n\n\
+                     Book 3: \"Dune\"\nAuthors: [Frank Herbert]\nGenres: []\nPublication Year: 1965\nSeries: Dune #1\n\n\
+                     Book 4: \"Cibola Burn\"\nAuthors: [James S. A. Corey]\nGenres: []\nPublication Year: 2014\nSeries: The Expanse #5\n";
+        let catalog = Catalog::parse(input)?;
+        assert_eq!(
+            catalog.books()[1].series(),
+            Some(&Series::new("The Expanse", 1))
+        );
+        assert_eq!(catalog.series_names(), ["The Expanse", "Dune"]);
+
+        // Тест на порядок читання та пропущені томи
+        let titles: Vec<&str> = catalog
+            .series("The Expanse")
+            .iter()
+            .map(|book| book.book_title())
+            .collect();
+        assert_eq!(titles, ["Leviathan Wakes", "Abaddon's Gate", "Cibola Burn"]);
+        assert_eq!(catalog.missing_volumes("The Expanse"), [2, 4]);
+        assert!(catalog.missing_volumes("Dune").is_empty());
+        assert!(catalog.series("Discworld").is_empty());
+
+        // Тест на JSON і запис
+        let json = catalog.to_json(&JsonOptions::default())?;
+        assert_eq!(json[0]["series"]["name"], "The Expanse");
+        assert_eq!(json[0]["series"]["index"], 3);
+        assert_eq!(Catalog::parse(&catalog.to_catalog_string())?, catalog);
+        let book = Book::builder()
+            .title("X")
+            .publication_year(2000)
+            .series("C# in Depth", 2)
+            .build()?;
+        assert!(book
+            .to_catalog_string()
+            .contains("Series: \"C# in Depth\" #2\n"));
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+
+        // Тест на некоректний номер тому
+        let result = "Book 1: \"X\"\nAuthors: []\nGenres: []\nPublication Year: 2000\nSeries: X #99999999999\n"
+            .parse::<Book>();
+        assert!(matches!(
+            result,
+            Err(ParseError::InvalidValue { ref field, .. }) if field == "series"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_genre_taxonomy() -> anyhow::Result<()> {
+        let taxonomy = GenreTaxonomy::from_json(
+            r#"[
+                {"name": "Science Fiction", "parent": "Fiction", "aliases": ["Sci-Fi"]},
+                {"name": "Fiction"},
+                {"name": "Thriller", "parent": "fiction"},
+                {"name": "Poetry"}
+            ]"#,
+        )?;
+
+        // Тест на назви, псевдоніми та ієрархію
+        assert_eq!(taxonomy.canonical("sci  fi"), Some("Science Fiction"));
+        assert_eq!(
+            taxonomy.canonical("SCIENCE-FICTION"),
+            Some("Science Fiction")
+        );
+        assert_eq!(taxonomy.parent("Thriller"), Some("Fiction"));
+        assert_eq!(taxonomy.ancestors("Sci-Fi"), ["Fiction"]);
+        assert!(taxonomy.is_within("Thriller", "Fiction"));
+        assert!(!taxonomy.is_within("Fiction", "Thriller"));
+
+        // Тест на нормалізацію жанрів під час розбору
+        let input = "Book 1: \"Dune\"\nAuthors: []\nGenres: [Sci-Fi, Science Fiction, Space Opera]\nPublication Year: 1965\n\n\
+                     Book 2: \"Gone Girl\"\nAuthors: []\nGenres: [thriller]\nPublication Year: 2012\n\n\
+                     Book 3: \"Kobzar\"\nAuthors: []\nGenres: [Poetry]\nPublication Year: 1840\n";
+        let options = ParseOptions {
+            taxonomy: Some(taxonomy.clone()),
+            ..ParseOptions::strict()
+        };
+        let parsed = Catalog::parse_with(input, &options)?;
+        let catalog = parsed.value;
+        assert_eq!(
+            catalog.books()[0].genres(),
+            ["Science Fiction", "Space Opera"]
+        );
+        assert_eq!(catalog.books()[1].genres(), ["Thriller"]);
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].field, "genres");
+        assert_eq!(parsed.warnings[0].location.line, 3);
+        assert!(parsed.warnings[0].message.contains("Space Opera"));
+
+        // Тест на пошук з урахуванням піджанрів
+        let titles: Vec<&str> = catalog
+            .books_in_genre("Fiction", &taxonomy)
+            .iter()
+            .map(|book| book.book_title())
+            .collect();
+        assert_eq!(titles, ["Dune", "Gone Girl"]);
+        assert_eq!(catalog.books_in_genre("space opera", &taxonomy).len(), 1);
+
+        // Тест на некоректну таксономію
+        assert!(matches!(
+            GenreTaxonomy::from_json(r#"[{"name": "A", "parent": "B"}]"#),
+            Err(TaxonomyError::UnknownParent { .. })
+        ));
+        assert!(matches!(
+            GenreTaxonomy::from_json(r#"[{"name": "A", "aliases": ["a"]}]"#),
+            Err(TaxonomyError::Duplicate(_))
+        ));
+        assert!(matches!(
+            GenreTaxonomy::from_json(
+                r#"[{"name": "A", "parent": "B"}, {"name": "B", "parent": "A"}]"#
+            ),
+            Err(TaxonomyError::Cycle(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_publication_date() -> anyhow::Result<()> {
+        // Тест на різні форми дати
+        assert_eq!(
+            PublicationDate::parse("2016")?,
+            PublicationDate::from_year(2016)?
+        );
+        assert_eq!(
+            PublicationDate::parse("March 2016")?,
+            PublicationDate::from_year_month(2016, 3)?
+        );
+        assert_eq!(PublicationDate::parse("Mar. 2016")?.month(), Some(3));
+        assert_eq!(
+            PublicationDate::parse("2016-03-14")?,
+            PublicationDate::from_date(Date::new(2016, 3, 14)?)?
+        );
+        let circa = PublicationDate::parse("c. 1850")?;
+        assert!(circa.is_circa());
+        assert_eq!(circa.to_string(), "c. 1850");
+        assert_eq!(PublicationDate::parse("400 BCE")?.year(), -400);
+        assert_eq!(PublicationDate::parse("-400")?.to_string(), "400 BCE");
+        assert!(PublicationDate::parse("2016-02-30").is_err());
+        assert!(PublicationDate::parse("Smarch 2016").is_err());
+        assert!(PublicationDate::parse("-400 BCE").is_err());
+
+        // Тест на розбір, JSON і запис
+        let head = "Book 1: \"X\"\nAuthors: []\nGenres: []\n";
+        for (line, json) in [
+            ("Publication Year: 2016\n", serde_json::json!(2016)),
+            (
+                "Publication Date: March 2016\n",
+                serde_json::json!("2016-03"),
+            ),
+            (
+                "Publication Year: ca. 1850 # guess\n",
+                serde_json::json!("c. 1850"),
+            ),
+            ("Publication Year: 8 BC\n", serde_json::json!("8 BCE")),
+        ] {
+            let book: Book = format!("{head}{line}").parse()?;
+            assert_eq!(
+                book.to_json(&JsonOptions::default())?["publication_year"],
+                json
+            );
+            assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+            assert_eq!(
+                serde_json::from_value::<Book>(serde_json::to_value(&book)?)?,
+                book
+            );
+        }
+        assert!(format!("{head}Publication Year: 0\n")
+            .parse::<Book>()
+            .is_err());
+
+        // Тест на конструктори: року 0 немає, місяць і день лише для нашої ери
+        assert_eq!(PublicationDate::from_year(0), Err(DateError::YearZero));
+        assert_eq!(
+            PublicationDate::from_year_month(-400, 3),
+            Err(DateError::EraMonth(-400))
+        );
+        assert!(PublicationDate::from_year_month(0, 3).is_err());
+        assert!(PublicationDate::from_date(Date::new(-400, 3, 14)?).is_err());
+        for date in [
+            PublicationDate::from_year(-400)?,
+            PublicationDate::from_year_month(2016, 3)?,
+        ] {
+            let book = Book::builder().title("X").publication_date(date).build()?;
+            assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+        }
+
+        // Тест на дату в майбутньому
+        let options = ParseOptions {
+            today: Some(Date::new(2024, 5, 1)?),
+            ..ParseOptions::strict()
+        };
+        for (date, warned) in [("2024-05-02", true), ("May 2024", false), ("2025", true)] {
+            let input = format!("{head}Publication Date: {date}\n");
+            let parsed = Catalog::parse_with(&input, &options)?;
+            assert_eq!(parsed.warnings.len(), usize::from(warned), "{date}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rating_scales() -> anyhow::Result<()> {
+        // Тест на шкали рейтингу та нормалізацію до 0–10
+        let stars = Rating::parse("4.5/5")?;
+        assert_eq!(stars.scale(), RatingScale::OutOf(5));
+        assert_eq!(stars.value(), 4.5);
+        assert_eq!(stars.normalized(), 9.0);
+        let percent = Rating::parse("87%")?;
+        assert_eq!(percent.scale(), RatingScale::Percent);
+        assert!((percent.normalized() - 8.7).abs() < 1e-6);
+        assert_eq!(Rating::parse("8")?, Rating::out_of_ten(8.0)?);
+
+        // Тест на значення поза шкалою
+        assert!(matches!(
+            Rating::parse("5.5/5"),
+            Err(RatingError::OutOfRange(_))
+        ));
+        assert!(matches!(
+            Rating::parse("101%"),
+            Err(RatingError::OutOfRange(_))
+        ));
+        assert!(matches!(
+            Rating::parse("10.9"),
+            Err(RatingError::OutOfRange(_))
+        ));
+        assert!(matches!(Rating::parse("1/0"), Err(RatingError::Scale)));
+        assert!(Rating::parse("4,5/5").is_err());
+
+        // Тест на розбір, JSON і збереження початкової форми
+        let head = "Book 1: \"X\"\nAuthors: []\nGenres: []\nPublication Year: 2000\n";
+        for (line, json) in [
+            ("Rating: 4.5/5\n", serde_json::json!("4.5/5")),
+            ("Rating: 87% # critics\n", serde_json::json!("87%")),
+            ("Rating: 9.5\n", serde_json::json!(9.5)),
+        ] {
+            let book: Book = format!("{head}{line}").parse()?;
+            assert_eq!(book.to_json(&JsonOptions::default())?["rating"], json);
+            assert!(book
+                .to_catalog_string()
+                .contains(line.split(" #").next().unwrap_or(line)));
+            assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+            assert_eq!(
+                serde_json::from_value::<Book>(serde_json::to_value(&book)?)?,
+                book
+            );
+        }
+        let book: Book = format!("{head}Rating: 4/5\n").parse()?;
+        assert_eq!(book.rating(), Some(8.0));
+
+        // Тест на рейтинг поза шкалою у файлі та в будівнику
+        let result = format!("{head}Rating: 6/5\n").parse::<Book>();
+        assert!(matches!(
+            result,
+            Err(ParseError::InvalidValue { ref field, ref value, .. }) if field == "rating" && value == "6/5"
+        ));
+        let built = Book::builder()
+            .title("X")
+            .publication_year(2000)
+            .scaled_rating(stars)
+            .build()?;
+        assert_eq!(built.rating(), Some(9.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ratings() -> anyhow::Result<()> {
+        // Тест на список оцінок з різних джерел
+        let line = "Ratings: [Goodreads 4.1/5 (12,000 votes), Internal 8/10, \"Top 100\" 90%]\n";
+        let pair = Grammar::parse(Rule::list_of_ratings, line)?
+            .next()
+            .ok_or_else(|| anyhow!("ratings not found"))?;
+        assert_eq!(pair.as_str(), line);
+        assert!(Grammar::parse(Rule::list_of_ratings, "Ratings: [Goodreads]\n").is_err());
+
+        let head = "Book 1: \"X\"\nAuthors: []\nGenres: []\nPublication Year: 2000\nRating: 7\n";
+        let input = format!(
+            "{head}Ratings: [Goodreads 4.1/5 (12,000 votes), Amazon UK 90% (1 vote), Internal 8/10]\n"
+        );
+        let book: Book = input.parse()?;
+        let ratings = book.ratings();
+        assert_eq!(ratings.len(), 3);
+        assert_eq!(ratings[0].source(), "Goodreads");
+        assert_eq!(ratings[0].rating(), Rating::parse("4.1/5")?);
+        assert_eq!(ratings[0].votes(), Some(12_000));
+        assert_eq!(ratings[1].source(), "Amazon UK");
+        assert_eq!(ratings[2].votes(), None);
+        assert_eq!(book.rating(), Some(7.0));
+
+        // Тест на зважену загальну оцінку
+        let expected = (8.2 * 12_000.0 + 9.0 + 8.0) / 12_002.0;
+        assert!((book.aggregate_rating().unwrap_or_default() - expected).abs() < 1e-4);
+        let expected = (8.2 * 12_000.0 + 9.0 + 8.0 * 12_000.0) / 24_001.0;
+        assert!((book.aggregate_rating_with(12_000.0).unwrap_or_default() - expected).abs() < 1e-4);
+        let unrated: Book = format!("{head}Ratings: []\n").parse()?;
+        assert_eq!(unrated.aggregate_rating(), Some(7.0));
+
+        // Тест на величезну кількість голосів без переповнення
+        let huge: Book =
+            format!("{head}Ratings: [A 5 (18446744073709551615 votes), B 5 (1 vote)]\n").parse()?;
+        assert_eq!(huge.aggregate_rating(), Some(5.0));
+
+        // Тест на JSON і запис
+        let json = book.to_json(&JsonOptions::default())?;
+        assert_eq!(json["ratings"][0]["rating"], "4.1/5");
+        assert_eq!(json["ratings"][0]["votes"], 12_000);
+        assert!(json["ratings"][2].get("votes").is_none());
+        assert_eq!(book.to_catalog_string().parse::<Book>()?, book);
+        assert_eq!(serde_json::from_value::<Book>(json)?, book);
+        let built = Book::builder()
+            .title("X")
+            .publication_year(2000)
+            .source_rating(SourceRating::new("Top 100", Rating::parse("90%")?, None))
+            .build()?;
+        assert!(built
+            .to_catalog_string()
+            .contains("Ratings: [\"Top 100\" 90%]\n"));
+        assert_eq!(built.to_catalog_string().parse::<Book>()?, built);
+
+        // Тест на оцінку поза шкалою
+        let result = format!("{head}Ratings: [Goodreads 6/5]\n").parse::<Book>();
+        assert!(matches!(
+            result,
+            Err(ParseError::InvalidValue { ref field, .. }) if field == "ratings"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prices() -> anyhow::Result<()> {
+        // Тест на ціну зі знижкою, форматом і датами дії
+        let line =
+            "Price: was 249.00 UAH now 199.00 UAH for e-book from 2026-11-01 until 2026-11-30\n";
+        let pair = Grammar::parse(Rule::price, line)?
+            .next()
+            .ok_or_else(|| anyhow!("price not found"))?;
+        assert_eq!(pair.as_str(), line);
+        assert!(Grammar::parse(Rule::price, "Price: was 249.00 UAH 199.00 UAH\n").is_err());
+        assert!(Grammar::parse(Rule::price, "Price: 199.00 UAH from 2026-11\n").is_err());
+
+        // Тест на кілька цін для різних валют і форматів
+        let head = "Book 1: \"X\"\nAuthors: []\nGenres: []\nPublication Year: 2000\n";
+        let input = format!(
+            "{head}Price: 320.00 UAH\nPrice: 180.00 UAH for ebook\nPrice: 9.99 USD\n\
+             Price: was 320.00 UAH now 250.00 UAH from 2026-11-01 until 2026-11-30\n"
+        );
+        let book: Book = input.parse()?;
+        assert_eq!(book.prices().len(), 4);
+        assert_eq!(book.price().map(Price::amount), Some("320.00".to_string()));
+        let sale = &book.prices()[3];
+        assert_eq!(sale.was(), Some(Price::parse("320", "UAH")?));
+        assert_eq!(sale.valid_until(), Some(Date::new(2026, 11, 30)?));
+
+        // Тест на вибір чинної ціни за валютою, форматом і датою
+        let uah: Currency = "UAH".parse()?;
+        let amount = |format, date| {
+            book.effective_price(uah, format, date)
+                .map(|tag| tag.price().to_string())
+        };
+        let october = Date::new(2026, 10, 18)?;
+        let november = Date::new(2026, 11, 30)?;
+        assert_eq!(amount(None, october), Some("180.00 UAH".to_string()));
+        assert_eq!(
+            amount(Some(Format::Paperback), october),
+            Some("320.00 UAH".to_string())
+        );
+        assert_eq!(
+            amount(Some(Format::Paperback), november),
+            Some("250.00 UAH".to_string())
+        );
+        assert_eq!(
+            amount(Some(Format::Ebook), november),
+            Some("180.00 UAH".to_string())
+        );
+        assert!(book
+            .effective_price("EUR".parse()?, None, october)
+            .is_none());
+
+        // Тест на JSON: `price` залишається, `prices` містить усі рядки
+        let json = serde_json::to_value(&book)?;
+        assert_eq!(json["price"]["amount"], "320.00");
+        assert_eq!(json["prices"][1]["format"], "ebook");
+        assert_eq!(json["prices"][3]["valid_from"], "2026-11-01");
+        let back: Book = serde_json::from_value(json)?;
+        assert_eq!(back, book);
+        assert_eq!(book.to_string().parse::<Book>()?, book);
+
+        // Тест на зміну та видалення другого рядка ціни в синтаксичному дереві
+        let mut tree = SyntaxTree::parse(&input)?;
+        let entry = tree.entry_mut(1).ok_or_else(|| anyhow!("no entry"))?;
+        assert_eq!(entry.fields_named("Price").count(), 4);
+        entry.set_field_at("Price", 1, "170.00 UAH for ebook")?;
+        assert_eq!(
+            entry.fields_named("Price").nth(1).map(|f| f.value()),
+            Some("170.00 UAH for ebook")
+        );
+        assert_eq!(entry.field("Price").map(|f| f.value()), Some("320.00 UAH"));
+        assert!(entry.set_field_at("Price", 2, "9.99 UAH").is_err());
+        let removed = entry.remove_field_at("Price", 2)?;
+        assert_eq!(
+            removed.map(|f| f.value().to_string()),
+            Some("9.99 USD".into())
+        );
+        assert_eq!(entry.to_book()?.prices().len(), 3);
+
+        // Тест на повтор тієї самої пропозиції та некоректну знижку
+        let repeated = format!("{head}Price: 180 UAH for ebook\nPrice: 150 UAH for e-book\n");
+        let error = repeated
+            .parse::<Book>()
+            .err()
+            .ok_or_else(|| anyhow!("expected a duplicate price"))?;
+        assert!(matches!(error, ParseError::DuplicateField { .. }));
+        assert!(Diagnostic::from_error(&error)
+            .render("books.txt", &repeated, false)
+            .contains(
+                "hint: two `Price:` lines may not share currency, format and validity dates"
+            ));
+        for line in [
+            "Price: was 200 USD now 150 UAH\n",
+            "Price: was 100 UAH now 150 UAH\n",
+            "Price: 150 UAH from 2026-12-01 until 2026-11-01\n",
+            "Price: 150 UAH until 2026-02-30\n",
+        ] {
+            let result = format!("{head}{line}").parse::<Book>();
+            assert!(
+                matches!(&result, Err(ParseError::InvalidValue { field, .. }) if field == "price"),
+                "Expected invalid price but got {:?}",
+                result
+            );
+        }
+
+        // Тест на будівник
+        let tag = PriceTag::new(Price::parse("150", "UAH")?).with_valid_from(november);
+        let built = Book::builder()
+            .title("X")
+            .publication_year(2000)
+            .price(Price::parse("199", "UAH")?)
+            .price_tag(tag.clone())
+            .build()?;
+        assert_eq!(built.prices()[1], tag);
+        assert!(Book::builder()
+            .title("X")
+            .publication_year(2000)
+            .price_tag(tag.clone())
+            .price_tag(tag)
+            .build()
+            .is_err());
+
+        Ok(())
+    }
 }